@@ -156,6 +156,15 @@ class AmtrakConnector(Connector):
             self._authenticated = False
             return False
     
+    async def probe(self) -> Optional[str]:
+        """Check the Amtraker API responds, reusing the connector's session"""
+        try:
+            session = await self._get_session()
+            async with session.get(f"{AMTRAKER_BASE_URL}/v3/stations/WAS") as response:
+                return None if response.status == 200 else f"HTTP {response.status}"
+        except Exception as e:
+            return str(e) or type(e).__name__
+    
     async def search(self, criteria: Dict[str, Any]) -> List[Dict[str, Any]]:
         """
         Get train information.
@@ -97,6 +97,16 @@ class CapitalBikeshareConnector(Connector):
         except Exception as e:
             print(f"Error loading station info: {e}")
     
+    async def probe(self) -> Optional[str]:
+        """Check the Capital Bikeshare GBFS feed responds, reusing the connector's client"""
+        if not self._client:
+            return "not connected"
+        try:
+            response = await self._client.get(SYSTEM_INFO)
+        except Exception as e:
+            return str(e) or type(e).__name__
+        return None if response.status_code == 200 else f"HTTP {response.status_code}"
+    
     async def search(self, criteria: Dict[str, Any]) -> List[Dict[str, Any]]:
         """
         Get bike availability.
@@ -104,6 +104,17 @@ class Connector(ABC):
         """Check if connector is functional"""
         return self._authenticated
     
+    async def probe(self) -> Optional[str]:
+        """
+        Check the service responds without changing connector state.
+        
+        Unlike authenticate() this reuses the existing client, so it's safe
+        to call repeatedly (e.g. from the self-test). Returns None when the
+        service is healthy, otherwise why it isn't. The default only reports
+        whether authenticate() succeeded.
+        """
+        return None if self._authenticated else "not authenticated"
+    
     async def refresh_auth(self) -> bool:
         """Refresh authentication if needed"""
         return await self.authenticate()
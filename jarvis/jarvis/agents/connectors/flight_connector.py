@@ -25,6 +25,7 @@ try:
 except ImportError:
     HTTPX_AVAILABLE = False
 
+import math
 import time
 from datetime import datetime, timedelta
 
@@ -33,6 +34,45 @@ from datetime import datetime, timedelta
 AVIATION_BASE_URL = "http://api.aviationstack.com/v1"
 FLIGHTS_ENDPOINT = f"{AVIATION_BASE_URL}/flights"
 
+# OpenSky Network base URL, overridable with config.extra["opensky_base_url"]
+OPENSKY_BASE_URL = "https://opensky-network.org/api"
+
+# OpenSky bounding box limits. Larger areas get rejected or return
+# thousands of state vectors, so the radius is clamped.
+MAX_TRAFFIC_RADIUS_MILES = 500
+MILES_PER_DEGREE_LAT = 69.0
+# cos(latitude) floor so lon_delta stays finite near the poles
+MIN_LAT_COSINE = 0.01
+
+
+def traffic_bbox(lat: float, lon: float, radius_miles: float) -> tuple:
+    """
+    Convert a center point and radius into an OpenSky bounding box.
+    
+    Returns (lamin, lomin, lamax, lomax). Raises ValueError for a
+    non-positive radius; radii above MAX_TRAFFIC_RADIUS_MILES are clamped.
+    """
+    if radius_miles <= 0:
+        raise ValueError(f"Traffic radius must be positive, got {radius_miles}")
+    
+    if radius_miles > MAX_TRAFFIC_RADIUS_MILES:
+        print(
+            f"Traffic radius {radius_miles} mi is too large for OpenSky, "
+            f"clamping to {MAX_TRAFFIC_RADIUS_MILES} mi"
+        )
+        radius_miles = MAX_TRAFFIC_RADIUS_MILES
+    
+    lat_deg = radius_miles / MILES_PER_DEGREE_LAT
+    cos_lat = max(math.cos(math.radians(lat)), MIN_LAT_COSINE)
+    lon_deg = min(radius_miles / (MILES_PER_DEGREE_LAT * cos_lat), 180.0)
+    
+    return (
+        max(lat - lat_deg, -90.0),      # lamin
+        max(lon - lon_deg, -180.0),     # lomin
+        min(lat + lat_deg, 90.0),       # lamax
+        min(lon + lon_deg, 180.0),      # lomax
+    )
+
 
 @dataclass
 class FlightStatus:
@@ -72,6 +112,7 @@ class FlightConnector(Connector):
     def __init__(self, config: ConnectorConfig):
         super().__init__(config)
         self._api_key = config.api_key
+        self._opensky_base_url = config.extra.get("opensky_base_url", OPENSKY_BASE_URL).rstrip("/")
         self._client: Optional[httpx.AsyncClient] = None
         # Rate limiting state
         self._rate_limited_until: Optional[datetime] = None
@@ -104,6 +145,24 @@ class FlightConnector(Connector):
         self._authenticated = True
         return True
     
+    async def probe(self) -> Optional[str]:
+        """
+        Check OpenSky responds, reusing the connector's client.
+        
+        AviationStack isn't called; its free tier only allows 100 calls a
+        month. A one-degree box keeps the OpenSky request cheap.
+        """
+        if not self._client:
+            return "not connected"
+        try:
+            response = await self._client.get(
+                f"{self._opensky_base_url}/states/all",
+                params={"lamin": 38.5, "lomin": -77.5, "lamax": 39.5, "lomax": -76.5},
+            )
+        except Exception as e:
+            return str(e) or type(e).__name__
+        return None if response.status_code == 200 else f"HTTP {response.status_code}"
+    
     async def search(self, criteria: Dict[str, Any]) -> List[Dict[str, Any]]:
         """
         Get flight status.
@@ -392,7 +451,12 @@ class FlightConnector(Connector):
         """
         Get live air traffic within radius using OpenSky Network.
         No API key required for anonymous access (lower rate limits).
+        
+        Raises ValueError if radius_miles <= 0. Radii above
+        MAX_TRAFFIC_RADIUS_MILES are clamped.
         """
+        bbox = traffic_bbox(lat, lon, radius_miles)
+        
         if not self._client:
             return []
         
@@ -401,19 +465,7 @@ class FlightConnector(Connector):
             # Silently skip this call while rate limited
             return []
             
-        # bbox = min_lat, min_lon, max_lat, max_lon
-        # Approx: 1 deg lat = 69 miles, 1 deg lon = ~53 miles (at 40 deg lat)
-        lat_deg = radius_miles / 69.0
-        lon_deg = radius_miles / 53.0
-        
-        bbox = (
-            lat - lat_deg,      # lamin
-            lon - lon_deg,      # lomin
-            lat + lat_deg,      # lamax
-            lon + lon_deg       # lomax
-        )
-        
-        url = "https://opensky-network.org/api/states/all"
+        url = f"{self._opensky_base_url}/states/all"
         params = {
             "lamin": bbox[0],
             "lomin": bbox[1],
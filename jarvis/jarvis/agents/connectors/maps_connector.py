@@ -7,6 +7,7 @@ Uses macOS Apple Maps via AppleScript to get travel times and directions.
 from __future__ import annotations
 
 import asyncio
+import shutil
 from typing import Any, Dict, List, Optional
 from datetime import datetime
 
@@ -74,6 +75,10 @@ class MapsConnector(Connector):
         if mode == "walking": return "w"
         return "d" # driving
     
+    async def probe(self) -> Optional[str]:
+        """Apple Maps is driven through osascript, so check that's available"""
+        return None if shutil.which("osascript") else "osascript not found (Apple Maps needs macOS)"
+    
     async def search(self, criteria: Dict[str, Any]) -> List[Dict[str, Any]]:
         """
         Search mostly returns the deep link for now.
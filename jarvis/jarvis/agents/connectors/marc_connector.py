@@ -144,6 +144,15 @@ class MARCConnector(Connector):
             self._authenticated = False
             return False
     
+    async def probe(self) -> Optional[str]:
+        """Check the MARC GTFS-RT feed responds, reusing the connector's session"""
+        try:
+            session = await self._get_session()
+            async with session.get(MARC_TRIP_UPDATES) as response:
+                return None if response.status == 200 else f"HTTP {response.status}"
+        except Exception as e:
+            return str(e) or type(e).__name__
+    
     async def search(self, criteria: Dict[str, Any]) -> List[Dict[str, Any]]:
         """
         Get MARC train information.
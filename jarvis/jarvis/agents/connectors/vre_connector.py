@@ -133,6 +133,15 @@ class VREConnector(Connector):
             self._authenticated = False
             return False
     
+    async def probe(self) -> Optional[str]:
+        """Check the VRE static GTFS feed responds, reusing the connector's session"""
+        try:
+            session = await self._get_session()
+            async with session.head(VRE_STATIC_GTFS) as response:
+                return None if response.status == 200 else f"HTTP {response.status}"
+        except Exception as e:
+            return str(e) or type(e).__name__
+    
     async def search(self, criteria: Dict[str, Any]) -> List[Dict[str, Any]]:
         """
         Get VRE train information.
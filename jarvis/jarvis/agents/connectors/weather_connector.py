@@ -7,6 +7,7 @@ Provides weather data for:
 - Current conditions (from nearest observation station)
 - 7-day forecasts (12-hour and hourly periods)
 - Weather alerts
+- Forecast office / grid metadata
 - Packing suggestions
 
 API Documentation: https://www.weather.gov/documentation/services-web-api
@@ -28,10 +29,11 @@ except ImportError:
     HTTPX_AVAILABLE = False
 
 
-# NOAA Weather.gov API endpoints
+# NOAA Weather.gov API endpoints (paths relative to the base URL, which
+# can be overridden with config.extra["base_url"] for mock servers or proxies)
 NWS_BASE_URL = "https://api.weather.gov"
-POINTS_ENDPOINT = f"{NWS_BASE_URL}/points"
-ALERTS_ENDPOINT = f"{NWS_BASE_URL}/alerts/active"
+POINTS_ENDPOINT = "/points"
+ALERTS_ENDPOINT = "/alerts/active"
 
 # User agent required by weather.gov
 USER_AGENT = "JARVIS-Assistant (github.com/jarvis-assistant)"
@@ -56,6 +58,7 @@ class WeatherConnector(Connector):
         super().__init__(config)
         self._client: Optional[httpx.AsyncClient] = None
         self._default_units = config.extra.get("units", "imperial")
+        self._base_url = config.extra.get("base_url", NWS_BASE_URL).rstrip("/")
         # Cache grid points to reduce API calls
         self._grid_cache: Dict[str, Dict[str, Any]] = {}
     
@@ -80,7 +83,7 @@ class WeatherConnector(Connector):
         
         # Test connection
         try:
-            response = await self._client.get(f"{NWS_BASE_URL}")
+            response = await self._client.get(self._base_url)
             if response.status_code == 200:
                 self._authenticated = True
                 print("✅ Connected to NOAA Weather Service (weather.gov) - No API key needed!")
@@ -90,6 +93,16 @@ class WeatherConnector(Connector):
             print(f"Weather API connection error: {e}")
             return False
     
+    async def probe(self) -> Optional[str]:
+        """Check weather.gov responds, reusing the connector's client"""
+        if not self._client:
+            return "not connected"
+        try:
+            response = await self._client.get(self._base_url)
+        except Exception as e:
+            return str(e) or type(e).__name__
+        return None if response.status_code == 200 else f"HTTP {response.status_code}"
+    
     async def search(self, criteria: Dict[str, Any]) -> List[Dict[str, Any]]:
         """
         Get weather data.
@@ -230,7 +243,7 @@ class WeatherConnector(Connector):
             return self._grid_cache[cache_key]
         
         try:
-            response = await self._client.get(f"{POINTS_ENDPOINT}/{lat},{lon}")
+            response = await self._client.get(f"{self._base_url}{POINTS_ENDPOINT}/{lat},{lon}")
             
             if response.status_code != 200:
                 print(f"Grid point error: {response.status_code}")
@@ -240,9 +253,12 @@ class WeatherConnector(Connector):
             properties = data.get("properties", {})
             
             grid_info = {
-                "office": properties.get("gridId"),
+                "office": properties.get("cwa") or properties.get("gridId"),
+                "grid_id": properties.get("gridId"),
                 "gridX": properties.get("gridX"),
                 "gridY": properties.get("gridY"),
+                "radar_station": properties.get("radarStation"),
+                "time_zone": properties.get("timeZone"),
                 "forecast_url": properties.get("forecast"),
                 "forecast_hourly_url": properties.get("forecastHourly"),
                 "observation_stations_url": properties.get("observationStations"),
@@ -257,6 +273,34 @@ class WeatherConnector(Connector):
             print(f"Grid point error: {e}")
             return None
     
+    async def get_weather_metadata(self, location: Any) -> Optional[Dict[str, Any]]:
+        """
+        Get NWS metadata for a location from the points response.
+        
+        Returns the forecast office, grid coordinates, nearest radar
+        station and time zone, so callers can link to the office page
+        or request gridpoint data directly. Uses the grid point cache.
+        """
+        if not self._client:
+            return None
+        
+        coords = await self._geocode(location) if isinstance(location, str) else location
+        if not coords:
+            return None
+        
+        grid_info = await self._get_grid_point(coords["lat"], coords["lon"])
+        if not grid_info:
+            return None
+        
+        return {
+            "office": grid_info.get("office"),
+            "grid_id": grid_info.get("grid_id"),
+            "grid_x": grid_info.get("gridX"),
+            "grid_y": grid_info.get("gridY"),
+            "radar_station": grid_info.get("radar_station"),
+            "time_zone": grid_info.get("time_zone"),
+        }
+    
     async def _get_current_conditions(
         self, 
         grid_info: Dict[str, Any]
@@ -280,7 +324,7 @@ class WeatherConnector(Connector):
             
             # Get latest observation from first (nearest) station
             station_id = stations[0]["properties"]["stationIdentifier"]
-            obs_url = f"{NWS_BASE_URL}/stations/{station_id}/observations/latest"
+            obs_url = f"{self._base_url}/stations/{station_id}/observations/latest"
             
             obs_response = await self._client.get(obs_url)
             if obs_response.status_code != 200:
@@ -395,7 +439,7 @@ class WeatherConnector(Connector):
         """Get active weather alerts for location"""
         try:
             response = await self._client.get(
-                ALERTS_ENDPOINT,
+                f"{self._base_url}{ALERTS_ENDPOINT}",
                 params={"point": f"{coords['lat']},{coords['lon']}"}
             )
             
@@ -12,8 +12,11 @@ API Key: Get free key at https://developer.wmata.com/
 
 from __future__ import annotations
 
-from datetime import datetime
-from typing import Any, Dict, List, Optional
+import re
+from datetime import datetime, timedelta
+from difflib import SequenceMatcher
+from enum import Enum
+from typing import Any, Dict, List, Optional, Tuple
 
 from jarvis.agents.connectors.connector_base import Connector, ConnectorConfig
 
@@ -25,13 +28,14 @@ except ImportError:
     HTTPX_AVAILABLE = False
 
 
-# WMATA API endpoints
+# WMATA API endpoints (paths relative to the base URL, which can be
+# overridden with config.extra["base_url"] for mock servers or proxies)
 WMATA_BASE_URL = "https://api.wmata.com"
-RAIL_PREDICTIONS = f"{WMATA_BASE_URL}/StationPrediction.svc/json/GetPrediction"
-BUS_PREDICTIONS = f"{WMATA_BASE_URL}/NextBusService.svc/json/jPredictions"
-RAIL_STATIONS = f"{WMATA_BASE_URL}/Rail.svc/json/jStations"
-BUS_STOPS = f"{WMATA_BASE_URL}/Bus.svc/json/jStops"
-ALERTS = f"{WMATA_BASE_URL}/Incidents.svc/json/Incidents"
+RAIL_PREDICTIONS = "/StationPrediction.svc/json/GetPrediction"
+BUS_PREDICTIONS = "/NextBusService.svc/json/jPredictions"
+RAIL_STATIONS = "/Rail.svc/json/jStations"
+BUS_STOPS = "/Bus.svc/json/jStops"
+ALERTS = "/Incidents.svc/json/Incidents"
 
 
 # Common DC Metro stations with codes
@@ -81,6 +85,92 @@ STATION_CODES = {
 }
 
 
+class PredictionKind(Enum):
+    """Meaning of a WMATA rail prediction "Min" value"""
+    BOARDING = "Boarding"    # "BRD"
+    ARRIVING = "Arriving"    # "ARR"
+    SCHEDULED = "On Time"    # numeric minutes
+    UNKNOWN = "Unknown"      # "---", blank, or anything else
+
+
+def parse_prediction_minutes(raw: Any) -> Tuple[PredictionKind, Optional[int]]:
+    """
+    Parse WMATA's raw "Min" field into a kind and minutes away.
+    
+    BRD and ARR count as 0 minutes. Unknown values return None minutes
+    so they can't be mistaken for a train arriving now.
+    """
+    value = str(raw if raw is not None else "").strip().upper()
+    
+    if value == "BRD":
+        return PredictionKind.BOARDING, 0
+    if value == "ARR":
+        return PredictionKind.ARRIVING, 0
+    if value.isdigit():
+        return PredictionKind.SCHEDULED, int(value)
+    return PredictionKind.UNKNOWN, None
+
+
+# Below this score a name is treated as no match at all
+MIN_STATION_SCORE = 0.6
+
+# Queries shorter than this only match exactly or by similarity ratio, since
+# one or two letters are a substring of most station names
+MIN_SUBSTRING_QUERY = 3
+
+
+def _contains_words(text: str, words: str) -> bool:
+    """Whether words appear in text as whole words"""
+    return re.search(rf"\b{re.escape(words)}\b", text) is not None
+
+
+def _station_score(query: str, name: str) -> float:
+    """
+    Score how well a station name matches a query, from 0.0 to 1.0.
+    
+    Exact names score 1.0. Whole-word matches either way round ("farragut",
+    "rosslyn station") score 0.9 and partial words typed as a prefix or
+    fragment ("farrag") score 0.8. These are flat so every station sharing
+    the words ties, e.g. both Farragut stations, and the caller can tell
+    the query is ambiguous. Everything else falls back to difflib's
+    similarity ratio.
+    """
+    if query == name:
+        return 1.0
+    if len(query) >= MIN_SUBSTRING_QUERY:
+        if _contains_words(name, query) or _contains_words(query, name):
+            return 0.9
+        if query in name:
+            return 0.8
+    return 0.8 * SequenceMatcher(None, query, name).ratio()
+
+
+def find_stations_by_name(query: str, limit: int = 5) -> List[Tuple[str, str, float]]:
+    """
+    Rank stations by how well their names match a query.
+    
+    Returns (name, code, score) tuples, best first, with ties broken by
+    name so the order is stable. Equal top scores mean the query is
+    ambiguous. Aliases sharing a station code only appear once, under
+    their best-scoring name.
+    """
+    query = " ".join(query.lower().split())
+    if not query:
+        return []
+    
+    best: Dict[str, Tuple[str, str, float]] = {}
+    for name, code in STATION_CODES.items():
+        score = _station_score(query, name)
+        if score < MIN_STATION_SCORE:
+            continue
+        current = best.get(code)
+        if current is None or (-score, name) < (-current[2], current[0]):
+            best[code] = (name, code, score)
+    
+    ranked = sorted(best.values(), key=lambda m: (-m[2], m[0]))
+    return ranked[:limit]
+
+
 class WMATAConnector(Connector):
     """
     WMATA API connector for DC Metro and Bus.
@@ -98,6 +188,7 @@ class WMATAConnector(Connector):
     def __init__(self, config: ConnectorConfig):
         super().__init__(config)
         self._api_key = config.api_key
+        self._base_url = config.extra.get("base_url", WMATA_BASE_URL).rstrip("/")
         self._client: Optional[httpx.AsyncClient] = None
     
     @property
@@ -123,7 +214,7 @@ class WMATAConnector(Connector):
         
         # Test API key
         try:
-            response = await self._client.get(ALERTS)
+            response = await self._client.get(self._url(ALERTS))
             if response.status_code == 401:
                 print("WMATA API key invalid")
                 return False
@@ -133,6 +224,20 @@ class WMATAConnector(Connector):
             print(f"WMATA connection error: {e}")
             return False
     
+    async def probe(self) -> Optional[str]:
+        """Check the WMATA API accepts the key, reusing the connector's client"""
+        if not self._api_key:
+            return "API key not configured"
+        if not self._client:
+            return "not connected"
+        try:
+            response = await self._client.get(self._url(ALERTS))
+        except Exception as e:
+            return str(e) or type(e).__name__
+        if response.status_code == 401:
+            return "API key rejected (HTTP 401)"
+        return None if response.status_code == 200 else f"HTTP {response.status_code}"
+    
     async def search(self, criteria: Dict[str, Any]) -> List[Dict[str, Any]]:
         """
         Get real-time predictions.
@@ -180,7 +285,7 @@ class WMATAConnector(Connector):
             station_code = "All"
         
         try:
-            url = f"{RAIL_PREDICTIONS}/{station_code}"
+            url = f"{self._url(RAIL_PREDICTIONS)}/{station_code}"
             response = await self._client.get(url)
             response.raise_for_status()
             
@@ -189,25 +294,14 @@ class WMATAConnector(Connector):
             
             results = []
             for train in trains[:limit]:
-                # Parse minutes
-                min_str = train.get("Min", "")
-                if min_str == "ARR":
-                    minutes = 0
-                elif min_str == "BRD":
-                    minutes = 0
-                elif min_str == "---":
+                # Parse minutes ("BRD", "ARR", "---" or a number)
+                kind, minutes = parse_prediction_minutes(train.get("Min"))
+                if kind == PredictionKind.UNKNOWN:
+                    # No usable ETA, skip rather than sort it as "now"
                     continue
-                else:
-                    try:
-                        minutes = int(min_str)
-                    except ValueError:
-                        minutes = 0
                 
                 # Calculate arrival time
-                arrival_time = datetime.now()
-                if minutes > 0:
-                    from datetime import timedelta
-                    arrival_time = arrival_time + timedelta(minutes=minutes)
+                arrival_time = datetime.now() + timedelta(minutes=minutes)
                 
                 results.append({
                     "route": train.get("Line", ""),
@@ -215,7 +309,7 @@ class WMATAConnector(Connector):
                     "destination": train.get("DestinationName", ""),
                     "time": arrival_time.isoformat(),
                     "minutes_away": minutes,
-                    "status": "Arriving" if min_str in ("ARR", "BRD") else "On Time",
+                    "status": kind.value,
                     "mode": "metro",
                     "cars": train.get("Car", ""),
                     "headsign": train.get("Destination", ""),
@@ -234,7 +328,7 @@ class WMATAConnector(Connector):
     ) -> List[Dict[str, Any]]:
         """Get Metrobus predictions for a stop"""
         try:
-            url = f"{BUS_PREDICTIONS}?StopID={stop_id}"
+            url = f"{self._url(BUS_PREDICTIONS)}?StopID={stop_id}"
             response = await self._client.get(url)
             response.raise_for_status()
             
@@ -249,7 +343,6 @@ class WMATAConnector(Connector):
                 # Calculate arrival time
                 arrival_time = datetime.now()
                 if minutes > 0:
-                    from datetime import timedelta
                     arrival_time = arrival_time + timedelta(minutes=minutes)
                 
                 results.append({
@@ -273,8 +366,12 @@ class WMATAConnector(Connector):
         """WMATA is read-only, no actions to execute"""
         return {"status": "info_only"}
     
+    def _url(self, path: str) -> str:
+        """Build a full endpoint URL from the configured base URL"""
+        return f"{self._base_url}{path}"
+    
     def _resolve_station(self, station: str) -> Optional[str]:
-        """Convert station name to WMATA station code"""
+        """Convert a station name or code to the best-matching WMATA station code"""
         if not station:
             return None
         
@@ -282,19 +379,15 @@ class WMATAConnector(Connector):
         if len(station) == 3 and station[0].isalpha() and station[1:].isdigit():
             return station.upper()
         
-        # Look up by name
-        station_lower = station.lower().strip()
-        
-        # Exact match
-        if station_lower in STATION_CODES:
-            return STATION_CODES[station_lower]
-        
-        # Partial match
-        for name, code in STATION_CODES.items():
-            if station_lower in name or name in station_lower:
-                return code
+        # Look up by name, taking the best-ranked match
+        matches = find_stations_by_name(station)
+        if not matches:
+            return None
         
-        return None
+        tied = [name for name, _, score in matches if score == matches[0][2]]
+        if len(tied) > 1:
+            print(f"Station '{station}' is ambiguous ({', '.join(tied)}); using {tied[0]}")
+        return matches[0][1]
     
     def _get_line_name(self, line_code: str) -> str:
         """Convert line code to full name"""
@@ -314,7 +407,7 @@ class WMATAConnector(Connector):
             return []
         
         try:
-            response = await self._client.get(ALERTS)
+            response = await self._client.get(self._url(ALERTS))
             response.raise_for_status()
             
             data = response.json()
@@ -340,7 +433,7 @@ class WMATAConnector(Connector):
             return []
         
         try:
-            response = await self._client.get(RAIL_STATIONS)
+            response = await self._client.get(self._url(RAIL_STATIONS))
             response.raise_for_status()
             
             data = response.json()
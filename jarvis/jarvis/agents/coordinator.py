@@ -330,7 +330,9 @@ Try asking for "next Metro from {station}" if you meant the subway."""
                     provider = f" ({dep.provider})" if hasattr(dep, 'provider') and dep.provider else ""
                     
                     # Format time nicely
-                    if hasattr(dep, 'minutes_away') and dep.minutes_away is not None:
+                    if getattr(dep, 'status', None) in ("Boarding", "Arriving"):
+                        time_str = dep.status
+                    elif hasattr(dep, 'minutes_away') and dep.minutes_away is not None:
                         time_str = f"{dep.minutes_away} min"
                     elif hasattr(dep, 'time'):
                         time_str = dep.time.strftime("%I:%M %p")
@@ -2,7 +2,7 @@
 
 import asyncio
 from pathlib import Path
-from typing import Optional
+from typing import List, Optional
 
 import typer
 from rich.console import Console
@@ -25,18 +25,40 @@ def get_orchestrator(config: Optional[Path] = None) -> JARVISOrchestrator:
     return JARVISOrchestrator(config_path=config)
 
 
+def get_memory_store():
+    """Open the configured memory database, as the running assistant does"""
+    from jarvis.core.memory_store import MemoryStore, ollama_embedder
+    
+    settings = load_config()
+    memory = settings.memory
+    embedder = (
+        ollama_embedder(memory.embedding_model, settings.ollama_host)
+        if memory.embedding_model else None
+    )
+    return MemoryStore(
+        memory.db_path,
+        default_importance=memory.default_importance,
+        embedder=embedder,
+        secret_categories=memory.secret_categories,
+    )
+
+
 @app.command()
 def chat(
     message: str = typer.Argument(..., help="Message to send to JARVIS"),
     speak: bool = typer.Option(True, "--speak/--no-speak", help="Speak the response"),
+    max_tokens: Optional[int] = typer.Option(None, "--max-tokens", help="Cap on response length in tokens"),
     config: Optional[Path] = typer.Option(None, "--config", "-c", help="Config file path"),
 ):
     """Send a message to JARVIS and get a response"""
     
     async def _chat():
         jarvis = get_orchestrator(config)
-        response = await jarvis.chat(message, speak=speak)
+        response = await jarvis.chat(message, speak=speak, max_tokens=max_tokens)
         console.print(Panel(Markdown(response), title="JARVIS", border_style="cyan"))
+        stats = jarvis.last_generation_stats
+        if stats and stats.truncated:
+            console.print("[yellow]Response cut off at the token limit; raise --max-tokens for more[/yellow]")
     
     asyncio.run(_chat())
 
@@ -125,6 +147,39 @@ def events(
     asyncio.run(_events())
 
 
+@app.command()
+def weather_info(
+    location: Optional[str] = typer.Argument(None, help="Location (defaults to the weather agent's default)"),
+    config: Optional[Path] = typer.Option(None, "--config", "-c", help="Config file path"),
+):
+    """Show the NWS forecast office, grid and radar station for a location"""
+    
+    async def _weather_info():
+        jarvis = get_orchestrator(config)
+        await jarvis.initialize()
+        
+        agent = jarvis.agent_coordinator.get_agent("weather") if jarvis.agent_coordinator else None
+        if not agent or not agent.connectors:
+            console.print("[red]Weather agent is not enabled[/red]")
+            raise typer.Exit(1)
+        
+        place = location or jarvis.settings.agents.weather.default_location
+        metadata = await agent.connectors[0].get_weather_metadata(place)
+        if not metadata:
+            console.print(f"[red]No weather.gov grid point found for '{place}'[/red]")
+            raise typer.Exit(1)
+        
+        lines = []
+        lines.append(f"Office: {metadata['office'] or 'unknown'}")
+        lines.append(f"Grid: {metadata['grid_id']} {metadata['grid_x']},{metadata['grid_y']}")
+        lines.append(f"Radar: {metadata['radar_station'] or 'unknown'}")
+        lines.append(f"Time zone: {metadata['time_zone'] or 'unknown'}")
+        
+        console.print(Panel("\n".join(lines), title=f"Weather metadata: {place}", border_style="cyan"))
+    
+    asyncio.run(_weather_info())
+
+
 @app.command()
 def tasks(
     add: Optional[str] = typer.Option(None, "--add", "-a", help="Add a new task"),
@@ -189,6 +244,78 @@ def health():
     asyncio.run(_health())
 
 
+@app.command()
+def selftest(
+    timeout: float = typer.Option(10.0, "--timeout", "-t", help="Seconds to wait for each service"),
+    config: Optional[Path] = typer.Option(None, "--config", "-c", help="Config file path"),
+):
+    """Check that weather, transit, flight and geolocation services respond"""
+    
+    async def _selftest():
+        jarvis = get_orchestrator(config)
+        results = await jarvis.run_integration_selftest(timeout=timeout)
+        
+        lines = []
+        for name, result in results.items():
+            if result["ok"]:
+                lines.append(f"[green]✓[/green] {name} ({result['latency_ms']} ms)")
+            else:
+                lines.append(f"[red]✗[/red] {name} ({result['latency_ms']} ms): {result['error']}")
+        
+        console.print(Panel("\n".join(lines), title="Self-Test", border_style="cyan"))
+        if not all(result["ok"] for result in results.values()):
+            raise typer.Exit(1)
+    
+    asyncio.run(_selftest())
+
+
+@app.command()
+def version():
+    """Show the JARVIS version, git commit, Python version and installed extras"""
+    from jarvis.core.version import get_version_info
+    
+    info = get_version_info()
+    
+    lines = []
+    lines.append(f"Version: {info['version']}")
+    lines.append(f"Commit: {info['git_commit'] or 'not a git checkout'}")
+    lines.append(f"Python: {info['python']}")
+    for extra, installed in info["extras"].items():
+        lines.append(f"Extra '{extra}': {'installed' if installed else 'not installed'}")
+    
+    console.print(Panel("\n".join(lines), title="JARVIS", border_style="cyan"))
+
+
+@app.command()
+def top(
+    limit: int = typer.Option(10, "--limit", "-n", help="Number of processes to show (max 50)"),
+    sort: str = typer.Option("cpu", "--sort", "-s", help="Sort by 'cpu' or 'memory'"),
+    exclude_self: bool = typer.Option(False, "--exclude-self", help="Leave JARVIS itself out of the list"),
+):
+    """Show the processes using the most CPU or memory"""
+    import time
+    from jarvis.core.system_stats import SystemStats
+    
+    stats = SystemStats()
+    try:
+        # CPU usage is measured between calls, so take a short sample first
+        stats.get_top_processes(limit=limit, sort_by=sort, exclude_self=exclude_self)
+        time.sleep(0.5)
+        processes = stats.get_top_processes(limit=limit, sort_by=sort, exclude_self=exclude_self)
+    except ValueError as e:
+        console.print(f"[red]{e}[/red]")
+        raise typer.Exit(1)
+    
+    lines = [f"[bold]{'PID':>7}  {'CPU%':>6}  {'MEM':>9}  NAME[/bold]"]
+    for proc in processes:
+        lines.append(
+            f"{proc['pid']:>7}  {proc['cpu_usage']:>6.1f}  "
+            f"{proc['memory'] / (1024 * 1024):>6.0f} MB  {proc['name']}"
+        )
+    
+    console.print(Panel("\n".join(lines), title=f"Top Processes by {sort}", border_style="cyan"))
+
+
 @app.command()
 def voices():
     """List available TTS voices"""
@@ -207,6 +334,120 @@ def voices():
     asyncio.run(_voices())
 
 
+@app.command()
+def model_info(
+    model: Optional[str] = typer.Argument(None, help="Model name (defaults to the primary model)"),
+    config: Optional[Path] = typer.Option(None, "--config", "-c", help="Config file path"),
+):
+    """Show a model's size, quantization, context window and load state"""
+    
+    async def _model_info():
+        jarvis = get_orchestrator(config)
+        await jarvis.initialize()
+        info = await jarvis.llm.get_model_info(model)
+        
+        if not info["installed"]:
+            console.print(f"[red]Model '{info['model']}' is not installed in Ollama[/red]")
+            raise typer.Exit(1)
+        
+        lines = []
+        lines.append(f"Family: {info['family'] or 'unknown'}")
+        lines.append(f"Parameters: {info['parameter_size'] or 'unknown'}")
+        lines.append(f"Quantization: {info['quantization'] or 'unknown'}")
+        lines.append(f"Max context: {info['context_length'] or 'unknown'}")
+        lines.append(f"Configured context: {info['context_size'] or 'Ollama default'}")
+        configured = jarvis.settings.llm.context_size
+        if configured and info["context_size"] != configured:
+            lines.append(f"[yellow]context_size {configured} exceeds the model maximum[/yellow]")
+        if info["embedding_length"]:
+            lines.append(f"Embedding size: {info['embedding_length']}")
+        if info["loaded"]:
+            lines.append(f"Loaded: yes ({info['gpu_percent']}% on GPU)")
+        else:
+            lines.append("Loaded: no")
+        
+        console.print(Panel("\n".join(lines), title=info["model"], border_style="cyan"))
+    
+    asyncio.run(_model_info())
+
+
+@app.command()
+def suggest_context(
+    model: Optional[str] = typer.Argument(None, help="Model name (defaults to the primary model)"),
+    budget_gb: Optional[float] = typer.Option(None, "--budget-gb", help="KV cache memory budget (default: half of available memory)"),
+    config: Optional[Path] = typer.Option(None, "--config", "-c", help="Config file path"),
+):
+    """Suggest an llm.context_size that fits in a memory budget"""
+    
+    async def _suggest_context():
+        import psutil
+        
+        jarvis = get_orchestrator(config)
+        await jarvis.initialize()
+        if budget_gb is not None:
+            budget = int(budget_gb * 1024 ** 3)
+        else:
+            budget = psutil.virtual_memory().available // 2
+        
+        info = await jarvis.llm.get_model_info(model)
+        if not info["installed"]:
+            console.print(f"[red]Model '{info['model']}' is not installed in Ollama[/red]")
+            raise typer.Exit(1)
+        suggestion = await jarvis.llm.suggest_context_size(budget, model)
+        if not suggestion:
+            console.print(f"[yellow]Model '{info['model']}' doesn't report its attention layout[/yellow]")
+            raise typer.Exit(1)
+        
+        lines = []
+        lines.append(f"Memory budget: {budget / 1024 ** 3:.1f} GB")
+        lines.append(f"Max context: {info['context_length'] or 'unknown'}")
+        lines.append(f"Suggested context_size: {suggestion['context_size']}")
+        lines.append(f"KV cache: {suggestion['kv_cache_bytes'] / 1024 ** 3:.2f} GB")
+        
+        console.print(Panel("\n".join(lines), title=info["model"], border_style="cyan"))
+    
+    asyncio.run(_suggest_context())
+
+
+@app.command()
+def models(
+    config: Optional[Path] = typer.Option(None, "--config", "-c", help="Config file path"),
+):
+    """List installed models and show which one is in use"""
+    
+    async def _models():
+        jarvis = get_orchestrator(config)
+        installed = await jarvis.list_models()
+        current = jarvis.settings.llm.primary_model
+        
+        lines = [
+            f"[cyan]▶ {name}[/cyan]" if name == current else f"  {name}"
+            for name in installed
+        ]
+        console.print(Panel("\n".join(lines) or "[dim]No models installed[/dim]", title="Models", border_style="cyan"))
+    
+    asyncio.run(_models())
+
+
+@app.command()
+def use_model(
+    name: str = typer.Argument(..., help="Installed model to use as the primary model"),
+    config: Optional[Path] = typer.Option(None, "--config", "-c", help="Config file path"),
+):
+    """Switch the primary model (remembered across restarts)"""
+    
+    async def _use_model():
+        jarvis = get_orchestrator(config)
+        try:
+            model = await jarvis.switch_model(name)
+        except ValueError as e:
+            console.print(f"[red]{e}[/red]")
+            raise typer.Exit(1)
+        console.print(f"[green]✓ Now using {model}[/green]")
+    
+    asyncio.run(_use_model())
+
+
 # Memory subcommands
 memory_app = typer.Typer(help="Memory management commands")
 app.add_typer(memory_app, name="memory")
@@ -215,9 +456,8 @@ app.add_typer(memory_app, name="memory")
 @memory_app.command("show")
 def memory_show():
     """Display stored user profile and preferences"""
-    from jarvis.core.memory_store import MemoryStore
     
-    memory = MemoryStore()
+    memory = get_memory_store()
     stats = memory.get_stats()
     profile = memory.get_user_profile()
     preferences = memory.get_all_preferences()
@@ -244,12 +484,275 @@ def memory_show():
     console.print(Panel("\n".join(lines), title="🧠 JARVIS Memory", border_style="cyan"))
 
 
+@memory_app.command("set")
+def memory_set(
+    category: str = typer.Argument(..., help="Preference category"),
+    pairs: List[str] = typer.Argument(..., help="One or more key=value pairs"),
+):
+    """Set one or more preferences in a category (all or nothing)"""
+    
+    items = []
+    for pair in pairs:
+        key, sep, value = pair.partition("=")
+        if not sep or not key:
+            console.print(f"[red]Expected key=value, got '{pair}'[/red]")
+            raise typer.Exit(1)
+        items.append((category, key, value))
+    
+    memory = get_memory_store()
+    memory.set_preferences(items)
+    console.print(f"[green]✓ Set {len(items)} preferences in '{category}'[/green]")
+
+
+@memory_app.command("unset")
+def memory_unset(
+    category: str = typer.Argument(..., help="Preference category"),
+    key: Optional[str] = typer.Argument(None, help="Preference key (omit to reset the whole category)"),
+):
+    """Delete a preference, or every preference in a category"""
+    
+    memory = get_memory_store()
+    if key:
+        memory.delete_preference(category, key)
+        console.print(f"[green]✓ Removed {category}/{key}[/green]")
+        return
+    
+    prefs = memory.get_preferences_by_category(category)
+    for pref in prefs:
+        memory.delete_preference(category, pref.key)
+    console.print(f"[green]✓ Removed {len(prefs)} preferences from '{category}'[/green]")
+
+
+@memory_app.command("snapshot")
+def memory_snapshot(
+    label: str = typer.Argument(..., help="Name for the snapshot (re-using one overwrites it)"),
+):
+    """Save a labeled snapshot of all preferences"""
+    
+    memory = get_memory_store()
+    count = memory.snapshot_preferences(label)
+    console.print(f"[green]✓ Saved snapshot '{label}' ({count} preferences)[/green]")
+
+
+@memory_app.command("diff")
+def memory_diff(
+    label_a: str = typer.Argument(..., help="Earlier snapshot"),
+    label_b: str = typer.Argument(..., help="Later snapshot"),
+):
+    """Show preferences added, removed or changed between two snapshots"""
+    
+    memory = get_memory_store()
+    try:
+        diff = memory.diff_preferences(label_a, label_b)
+    except ValueError as e:
+        console.print(f"[red]{e}[/red]")
+        raise typer.Exit(1)
+    
+    lines = []
+    for key, value in diff["added"].items():
+        lines.append(f"[green]+ {key}: {value}[/green]")
+    for key, value in diff["removed"].items():
+        lines.append(f"[red]- {key}: {value}[/red]")
+    for key, (old, new) in diff["changed"].items():
+        lines.append(f"[yellow]~ {key}: {old} → {new}[/yellow]")
+    
+    console.print(Panel(
+        "\n".join(lines) or "[dim]No differences[/dim]",
+        title=f"{label_a} → {label_b}",
+        border_style="cyan",
+    ))
+
+
+@memory_app.command("conflicts")
+def memory_conflicts():
+    """Show preferences with the same meaning stored under several categories"""
+    
+    memory = get_memory_store()
+    conflicts = memory.find_conflicting_preferences()
+    if not conflicts:
+        console.print("[green]✓ No conflicting preferences[/green]")
+        return
+    
+    lines = []
+    for conflict in conflicts:
+        color = "yellow" if conflict["values_differ"] else "dim"
+        lines.append(f"[bold {color}]{conflict['key']}[/bold {color}]")
+        for entry in conflict["entries"]:
+            lines.append(f"  {entry['category']}/{entry['key']}: {entry['value']}")
+    
+    console.print(Panel(
+        "\n".join(lines),
+        title=f"⚠️ {len(conflicts)} conflicting preferences",
+        border_style="cyan",
+    ))
+
+
+@memory_app.command("list")
+def memory_list(
+    category: Optional[str] = typer.Option(None, "--category", "-c", help="Only show this category"),
+    limit: int = typer.Option(20, "--limit", "-n", help="Memories per page"),
+    offset: int = typer.Option(0, "--offset", help="Number of memories to skip"),
+):
+    """Browse stored memories, newest first"""
+    
+    memory = get_memory_store()
+    page = memory.list_memories(category=category, limit=limit, offset=offset)
+    
+    if not page.items:
+        console.print("[yellow]No memories found[/yellow]")
+        return
+    
+    lines = [
+        f"  #{mem.id} [{mem.category}] ({mem.importance}) {mem.content}"
+        for mem in page.items
+    ]
+    lines.append(
+        f"\n[dim]Showing {offset + 1}-{offset + len(page.items)} of {page.total}[/dim]"
+    )
+    
+    console.print(Panel("\n".join(lines), title="🧠 JARVIS Memories", border_style="cyan"))
+
+
+@memory_app.command("search")
+def memory_search(
+    query: str = typer.Argument(..., help="Text to search for"),
+    limit: int = typer.Option(5, "--limit", "-n", help="Max results per source"),
+):
+    """Search memories, preferences and past conversations"""
+    from jarvis.core.interaction_store import InteractionStore
+    
+    memory = get_memory_store()
+    results = memory.global_search(query, limit=limit, interactions=InteractionStore())
+    
+    if not results:
+        console.print(f"[yellow]Nothing found for '{query}'[/yellow]")
+        return
+    
+    lines = [f"  [cyan]{r.source}[/cyan] {r.text}" for r in results]
+    console.print(Panel("\n".join(lines), title=f"🔎 {query}", border_style="cyan"))
+
+
+@memory_app.command("delete")
+def memory_delete(
+    memory_id: int = typer.Argument(..., help="ID of the memory to delete"),
+):
+    """Delete a single memory by ID"""
+    
+    memory = get_memory_store()
+    if memory.delete_memory(memory_id):
+        console.print(f"[green]✓ Deleted memory #{memory_id}[/green]")
+    else:
+        console.print(f"[yellow]No memory with ID {memory_id}[/yellow]")
+
+
+@memory_app.command("edit")
+def memory_edit(
+    memory_id: int = typer.Argument(..., help="ID of the memory to edit"),
+    content: Optional[str] = typer.Option(None, "--content", help="New memory text"),
+    category: Optional[str] = typer.Option(None, "--category", "-c", help="New category"),
+    importance: Optional[int] = typer.Option(None, "--importance", "-i", help="New importance (1-10)"),
+):
+    """Edit a stored memory"""
+    
+    memory = get_memory_store()
+    try:
+        memory.update_memory(memory_id, content=content, category=category, importance=importance)
+    except KeyError:
+        console.print(f"[red]No memory with ID {memory_id}[/red]")
+        raise typer.Exit(1)
+    console.print(f"[green]✓ Updated memory #{memory_id}[/green]")
+
+
+@memory_app.command("floor")
+def memory_floor(
+    category: Optional[str] = typer.Argument(None, help="Memory category"),
+    floor: Optional[int] = typer.Argument(None, help="Minimum importance (1-10)"),
+):
+    """Show or set per-category importance floors"""
+    
+    memory = get_memory_store()
+    if category and floor is not None:
+        memory.set_importance_floor(category, floor)
+        console.print(f"[green]✓ Importance floor for '{category}' set[/green]")
+        return
+    
+    floors = memory.get_importance_floors()
+    if category:
+        floors = {category: floors[category]} if category in floors else {}
+    if not floors:
+        console.print("[dim]No importance floors set[/dim]")
+        return
+    for cat, value in sorted(floors.items()):
+        console.print(f"  {cat}: [cyan]{value}[/cyan]")
+
+
+@memory_app.command("prune")
+def memory_prune(
+    below: int = typer.Option(..., "--below", "-b", help="Delete memories with importance below this"),
+):
+    """Delete low-importance memories (categories with a floor are never pruned)"""
+    
+    memory = get_memory_store()
+    removed = memory.prune_memories(below)
+    console.print(f"[green]✓ Pruned {removed} memories[/green]")
+
+
+@memory_app.command("purge-expired")
+def memory_purge_expired():
+    """Delete memories whose TTL has passed"""
+    
+    memory = get_memory_store()
+    removed = memory.purge_expired_memories()
+    console.print(f"[green]✓ Purged {removed} expired memories[/green]")
+
+
+@memory_app.command("export")
+def memory_export(
+    path: Path = typer.Argument(..., help="JSON file to write"),
+):
+    """Back up profile, preferences and memories to JSON"""
+    
+    memory = get_memory_store()
+    counts = memory.export_memory(str(path))
+    console.print(
+        f"[green]✓ Exported {counts['memories']} memories, "
+        f"{counts['preferences']} preferences, {counts['facts']} facts to {path}[/green]"
+    )
+    if counts["sensitive"]:
+        console.print(
+            f"[yellow]⚠ {counts['sensitive']} secret preferences are stored unencrypted "
+            f"in {path}; keep it somewhere safe[/yellow]"
+        )
+
+
+@memory_app.command("import")
+def memory_import(
+    path: Path = typer.Argument(..., help="JSON file from 'memory export'"),
+    merge: bool = typer.Option(False, "--merge", "-m", help="Merge instead of replacing"),
+    force: bool = typer.Option(False, "--force", "-f", help="Skip confirmation"),
+):
+    """Restore memory from a JSON backup"""
+    
+    if not merge and not force:
+        confirm = typer.confirm("Importing without --merge replaces all JARVIS memories. Continue?")
+        if not confirm:
+            console.print("[yellow]Cancelled[/yellow]")
+            return
+    
+    # The configured secret categories make sure imported secrets are re-encrypted
+    memory = get_memory_store()
+    counts = memory.import_memory(str(path), merge=merge)
+    console.print(
+        f"[green]✓ Imported {counts['memories']} memories, "
+        f"{counts['preferences']} preferences, {counts['facts']} facts[/green]"
+    )
+
+
 @memory_app.command("clear")
 def memory_clear(
     force: bool = typer.Option(False, "--force", "-f", help="Skip confirmation"),
 ):
     """Clear all stored memories (with confirmation)"""
-    from jarvis.core.memory_store import MemoryStore
     
     if not force:
         confirm = typer.confirm("Are you sure you want to clear all JARVIS memories?")
@@ -257,7 +760,7 @@ def memory_clear(
             console.print("[yellow]Cancelled[/yellow]")
             return
     
-    memory = MemoryStore()
+    memory = get_memory_store()
     memory.clear_all()
     console.print("[green]✓ All memories cleared[/green]")
 
@@ -267,13 +770,32 @@ def memory_set_name(
     name: str = typer.Argument(..., help="Your name"),
 ):
     """Set your name directly"""
-    from jarvis.core.memory_store import MemoryStore
     
-    memory = MemoryStore()
+    memory = get_memory_store()
     memory.set_user_name(name)
     console.print(f"[green]✓ Name set to: {name}[/green]")
 
 
+@memory_app.command("forget-fact")
+def memory_forget_fact(
+    fact: Optional[str] = typer.Argument(None, help="Exact fact text to remove"),
+    all_facts: bool = typer.Option(False, "--all", help="Remove every stored fact"),
+):
+    """Remove a stored fact about you"""
+    
+    memory = get_memory_store()
+    if all_facts:
+        memory.clear_user_facts()
+        console.print("[green]✓ All facts cleared[/green]")
+    elif fact is None:
+        console.print("[red]Give a fact to remove, or --all[/red]")
+        raise typer.Exit(1)
+    elif memory.remove_user_fact(fact):
+        console.print(f"[green]✓ Removed fact: {fact}[/green]")
+    else:
+        console.print(f"[yellow]No stored fact matches: {fact}[/yellow]")
+
+
 # Training subcommands
 train_app = typer.Typer(help="Training data and model customization commands")
 app.add_typer(train_app, name="train")
@@ -15,7 +15,18 @@ class LLMConfig(BaseModel):
     primary_model: str = "qwen2.5:14b-instruct-q5_K_M"
     fallback_model: str = "phi4:latest"
     temperature: float = 0.7
-    max_tokens: int = 2048
+    max_tokens: int = 2048  # Default completion cap; trimmed to what the prompt leaves of the context window
+    warmup: bool = True  # Preload the primary model when the UI or voice loop starts
+    top_p: Optional[float] = None  # Nucleus sampling; None uses the model default
+    top_k: Optional[int] = None
+    seed: Optional[int] = None     # Fix for reproducible output
+    context_size: Optional[int] = None  # Ollama num_ctx; None uses the server default
+    gpu_layers: Optional[int] = None    # Layers to offload to GPU (num_gpu); None lets Ollama decide, 0 = CPU only
+    repeat_penalty: Optional[float] = None  # 0.5-2; >1 discourages repeated phrases
+    repeat_last_n: Optional[int] = None     # How many recent tokens the penalty looks at (-1 = whole context)
+    frequency_penalty: Optional[float] = None  # -2 to 2
+    presence_penalty: Optional[float] = None   # -2 to 2
+    stop: Optional[List[str]] = None    # Stop sequences; replaces the model's own list when set
 
 
 class STTConfig(BaseModel):
@@ -62,6 +73,10 @@ class MemoryConfig(BaseModel):
     enabled: bool = True
     db_path: str = "~/.jarvis/memory.db"
     auto_extract_preferences: bool = True
+    default_importance: int = 5  # Used when a memory is stored without importance (1-10)
+    embedding_model: Optional[str] = None  # Ollama embedding model (e.g. nomic-embed-text) for semantic search
+    secret_categories: List[str] = []  # Preference categories encrypted at rest (needs the "secrets" extra)
+    context_max_chars: Optional[int] = None  # Cap on memory context added to the system prompt (0 = no cap, None = derive from llm.context_size)
 
 
 
@@ -89,11 +104,13 @@ class WeatherAgentConfig(BaseModel):
     provider: str = "weather.gov"
     default_location: str = "Washington, DC"
     units: str = "imperial"
+    base_url: Optional[str] = None  # Override api.weather.gov (mock server, proxy)
 
 class FlightAgentConfig(BaseModel):
     enabled: bool = True
     provider: str = "aviationstack"
     api_key: str = ""
+    opensky_base_url: Optional[str] = None  # Override the OpenSky API base URL
 
 class TripAgentConfig(BaseModel):
     enabled: bool = True
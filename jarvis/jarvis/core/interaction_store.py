@@ -310,6 +310,32 @@ class InteractionStore:
                 for row in cursor.fetchall()
             ]
     
+    def search_messages(self, query: str, limit: int = 10) -> List[Message]:
+        """Search message content by keyword, newest first"""
+        with sqlite3.connect(self.db_path) as conn:
+            cursor = conn.cursor()
+            cursor.execute("""
+                SELECT id, conversation_id, role, content, tokens, model, created_at, metadata
+                FROM messages
+                WHERE content LIKE ?
+                ORDER BY created_at DESC
+                LIMIT ?
+            """, (f"%{query}%", limit))
+            
+            return [
+                Message(
+                    id=row[0],
+                    conversation_id=row[1],
+                    role=row[2],
+                    content=row[3],
+                    tokens=row[4],
+                    model=row[5],
+                    created_at=datetime.fromisoformat(row[6]) if row[6] else None,
+                    metadata=json.loads(row[7]) if row[7] else {},
+                )
+                for row in cursor.fetchall()
+            ]
+    
     # ========== Tool Call Methods ==========
     
     def log_tool_call(
@@ -40,12 +40,28 @@ class ToolCall:
     arguments: dict
 
 
+@dataclass
+class GenerationStats:
+    """Token counts and timing for one generation"""
+    prompt_tokens: Optional[int] = None
+    completion_tokens: Optional[int] = None
+    generation_ms: Optional[float] = None
+    truncated: bool = False  # Stopped by the max_tokens cap, not naturally
+    
+    @property
+    def tokens_per_second(self) -> Optional[float]:
+        if not self.completion_tokens or not self.generation_ms:
+            return None
+        return self.completion_tokens / (self.generation_ms / 1000)
+
+
 @dataclass
 class LLMResponse:
     """Response from an LLM query"""
     content: str
     tool_calls: List[ToolCall] = field(default_factory=list)
     raw_response: Optional[Any] = None
+    stats: Optional[GenerationStats] = None
 
 
 class LLMEngine(ABC):
@@ -57,8 +73,13 @@ class LLMEngine(ABC):
     - Basic text generation
     - Tool/function calling
     - Streaming responses
+    
+    Providers that can measure it set ``last_stream_stats`` when a
+    stream finishes.
     """
     
+    last_stream_stats: Optional[GenerationStats] = None
+    
     @abstractmethod
     async def reason(
         self,
@@ -66,6 +87,7 @@ class LLMEngine(ABC):
         tools: Optional[list[Tool]] = None,
         system_prompt: Optional[str] = None,
         conversation_history: Optional[list[dict]] = None,
+        max_tokens: Optional[int] = None,
     ) -> LLMResponse:
         """
         Generate a response from the LLM.
@@ -75,6 +97,7 @@ class LLMEngine(ABC):
             tools: Available tools the LLM can call
             system_prompt: System instructions for the LLM
             conversation_history: Previous messages for context
+            max_tokens: Completion length cap (defaults to the provider's)
             
         Returns:
             LLMResponse with content and any tool calls
@@ -86,6 +109,7 @@ class LLMEngine(ABC):
         self,
         prompt: str,
         system_prompt: Optional[str] = None,
+        max_tokens: Optional[int] = None,
     ) -> AsyncIterator[str]:
         """
         Stream response tokens for real-time TTS.
@@ -98,3 +122,15 @@ class LLMEngine(ABC):
     async def health_check(self) -> bool:
         """Check if the LLM backend is available"""
         pass
+    
+    async def warmup(self) -> None:
+        """Load the model ahead of the first request (optional, no-op by default)"""
+        pass
+    
+    async def context_window(self) -> Optional[int]:
+        """Context window in tokens the backend actually requests, if known (optional)"""
+        return None
+    
+    async def list_models(self) -> List[str]:
+        """Models this backend can switch between (optional)"""
+        return []
@@ -4,18 +4,44 @@ JARVIS Memory Store - Persistent memory for user identity and preferences
 Provides SQLite-backed storage for:
 - User profile (name, identity, key facts)
 - Preferences (likes, dislikes, preferred styles)
-- Long-term memories (facts, context)
+- Long-term memories (facts, context), with optional embedding-based
+  semantic search
+
+Preferences in "secret" categories can be encrypted at rest with a key
+kept in the OS keychain (requires the "secrets" extra).
 """
 
 from __future__ import annotations
 
+import logging
+import math
+import re
 import sqlite3
+from array import array
 from dataclasses import dataclass, field
-from datetime import datetime
+from datetime import datetime, timezone
 from pathlib import Path
-from typing import List, Optional
+from typing import TYPE_CHECKING, Callable, Dict, Generic, List, Optional, Tuple, TypeVar
 import json
 
+if TYPE_CHECKING:
+    from jarvis.core.interaction_store import InteractionStore
+
+try:
+    import keyring
+    from cryptography.fernet import Fernet
+    SECRETS_AVAILABLE = True
+except ImportError:
+    SECRETS_AVAILABLE = False
+
+
+logger = logging.getLogger(__name__)
+
+T = TypeVar("T")
+
+# Turns text into an embedding vector
+Embedder = Callable[[str], List[float]]
+
 
 @dataclass
 class UserProfile:
@@ -41,9 +67,136 @@ class Memory:
     id: Optional[int] = None
     content: str = ""
     category: str = "general"  # fact, preference, context, personal
-    importance: int = 5        # 1-10 scale
+    importance: int = 5        # 1-10 scale (10 = critical)
     created_at: Optional[datetime] = None
     last_accessed: Optional[datetime] = None
+    score: Optional[float] = None  # search relevance (higher = better), if from a search
+
+
+@dataclass
+class PagedResult(Generic[T]):
+    """One page of results plus the total count for pagination"""
+    items: List[T] = field(default_factory=list)
+    total: int = 0
+    limit: int = 0
+    offset: int = 0
+
+
+@dataclass
+class SearchResult:
+    """A global search hit tagged with where it came from"""
+    source: str                # "memory", "preference", "conversation"
+    text: str
+    score: float
+    ref: Optional[str] = None  # memory/message id or "category/key"
+
+
+# How long a connection waits for another writer's lock before
+# raising "database is locked"
+BUSY_TIMEOUT_MS = 5000
+
+# Memories with no expiry, or one still in the future
+_NOT_EXPIRED = "(expires_at IS NULL OR expires_at > CURRENT_TIMESTAMP)"
+
+
+def _match_score(text: str, query: str) -> float:
+    """Rough relevance: exact match > whole-word match > substring match > none"""
+    text_lower, query_lower = text.lower().strip(), query.lower().strip()
+    if text_lower == query_lower:
+        return 1.0
+    if re.search(rf"\b{re.escape(query_lower)}\b", text_lower):
+        return 0.75
+    if query_lower in text_lower:
+        return 0.5
+    return 0.0
+
+
+def effective_importance(
+    importance: int,
+    last_accessed: Optional[datetime | str],
+    half_life_days: float,
+    now: Optional[datetime] = None
+) -> float:
+    """
+    Importance decayed by time since last access, halving every half_life_days.
+    
+    Timestamps are naive UTC, as SQLite's CURRENT_TIMESTAMP stores them.
+    A non-positive half-life disables decay.
+    """
+    if not last_accessed or half_life_days <= 0:
+        return float(importance)
+    if isinstance(last_accessed, str):
+        last_accessed = datetime.fromisoformat(last_accessed)
+    now = now or datetime.now(timezone.utc).replace(tzinfo=None)
+    age_days = max(0.0, (now - last_accessed).total_seconds() / 86400)
+    return importance * 0.5 ** (age_days / half_life_days)
+
+
+def _fts_query(query: str) -> str:
+    """
+    Turn user input into an FTS5 MATCH expression.
+    
+    Input already using FTS syntax (quoted phrases, trailing *) is passed
+    through; otherwise each word becomes a quoted prefix term so partial
+    words still match and punctuation can't break the query.
+    """
+    if '"' in query or query.rstrip().endswith("*"):
+        return query
+    return " ".join(f'"{token}"*' for token in re.findall(r"\w+", query))
+
+
+def _encode_embedding(vector: List[float]) -> bytes:
+    return array("f", vector).tobytes()
+
+
+def _decode_embedding(blob: bytes) -> List[float]:
+    vector = array("f")
+    vector.frombytes(blob)
+    return vector.tolist()
+
+
+def _cosine_similarity(a: List[float], b: List[float]) -> float:
+    if len(a) != len(b):
+        return 0.0
+    dot = sum(x * y for x, y in zip(a, b))
+    norm = math.sqrt(sum(x * x for x in a)) * math.sqrt(sum(y * y for y in b))
+    return dot / norm if norm else 0.0
+
+
+def ollama_embedder(model: str, host: Optional[str] = None) -> Embedder:
+    """Build an embedder backed by an Ollama embedding model"""
+    import ollama
+    
+    client = ollama.Client(host=host) if host else ollama.Client()
+    
+    def embed(text: str) -> List[float]:
+        return list(client.embeddings(model=model, prompt=text)["embedding"])
+    
+    return embed
+
+
+# Marks a preference value encrypted by MemoryStore
+_ENCRYPTED_PREFIX = "enc:v1:"
+
+# Shown in place of secret preference values in search results
+SECRET_MASK = "********"
+
+# Keychain entry holding the preference encryption key
+_KEYRING_USERNAME = "memory-store-key"
+
+
+def keyring_cipher(service: str = "jarvis") -> "Fernet":
+    """Get a Fernet cipher keyed from the OS keychain, creating the key on first use"""
+    if not SECRETS_AVAILABLE:
+        raise RuntimeError(
+            "Encrypted preferences need keyring and cryptography "
+            "(pip install 'jarvis[secrets]')"
+        )
+    key = keyring.get_password(service, _KEYRING_USERNAME)
+    if key is None:
+        key = Fernet.generate_key().decode()
+        keyring.set_password(service, _KEYRING_USERNAME, key)
+    return Fernet(key.encode())
 
 
 class MemoryStore:
@@ -52,21 +205,94 @@ class MemoryStore:
     
     Stores user profile, preferences, and memories that persist
     across JARVIS sessions.
+    
+    Memory importance uses a 1-10 scale where 1 is trivia and 10 is
+    critical. Out-of-range values are clamped; a missing (None/0)
+    importance falls back to ``default_importance``. Categories can
+    have an importance floor that memories are raised to and that
+    prune_memories never deletes below.
+    
+    With an ``embedder``, memories are embedded on insert and
+    search_memories ranks by cosine similarity. Without one (or if
+    embedding fails) it falls back to FTS5 keyword search ranked by bm25.
+    
+    Preference values in ``secret_categories`` are encrypted before they
+    are stored and decrypted transparently on read; other categories stay
+    plaintext.
     """
     
-    def __init__(self, db_path: Optional[str] = None):
+    MIN_IMPORTANCE = 1
+    MAX_IMPORTANCE = 10
+    
+    # Logical preference name -> keys that mean the same thing
+    PREFERENCE_ALIASES: Dict[str, List[str]] = {
+        "latitude": ["latitude", "lat"],
+        "longitude": ["longitude", "lon", "lng"],
+        "city": ["city", "location", "home_city"],
+        "timezone": ["timezone", "time_zone", "tz"],
+        "wmata_api_key": ["wmata_api_key", "wmata_key"],
+    }
+    
+    # Preference category holding per-memory-category importance floors
+    IMPORTANCE_FLOOR_CATEGORY = "memory_importance_floor"
+    
+    # Preference ("memory", "decay_half_life_days") tunes search importance decay
+    DECAY_PREFERENCE = ("memory", "decay_half_life_days")
+    DEFAULT_DECAY_HALF_LIFE_DAYS = 30.0
+    
+    # Semantic hits below this cosine similarity are treated as unrelated
+    MIN_SIMILARITY = 0.3
+    
+    # Schema migrations as (version, description, method name), applied in
+    # order and tracked in PRAGMA user_version. Each step must be idempotent:
+    # databases from before versioning may already have it.
+    MIGRATIONS: List[Tuple[int, str, str]] = [
+        (1, "add memories.embedding", "_migrate_memory_embeddings"),
+        (2, "add memories_fts full-text index", "_migrate_memory_fts"),
+        (3, "add memories.expires_at", "_migrate_memory_expiry"),
+    ]
+    
+    def __init__(
+        self,
+        db_path: Optional[str] = None,
+        default_importance: int = 5,
+        embedder: Optional[Embedder] = None,
+        secret_categories: Optional[List[str]] = None,
+        cipher: Optional["Fernet"] = None
+    ):
         if db_path is None:
             db_path = str(Path.home() / ".jarvis" / "memory.db")
         
+        self.default_importance = self._clamp_importance(default_importance)
+        self.embedder = embedder
+        self.secret_categories = set(secret_categories or [])
+        # Bumped on every preference write through this store, so callers
+        # can cache preferences and re-read them only after a change
+        self.preferences_version = 0
+        self._cipher = cipher
+        if self.secret_categories and self._cipher is None:
+            # Fail now rather than on the first secret write
+            self._cipher = keyring_cipher()
         self.db_path = Path(db_path).expanduser()
         self.db_path.parent.mkdir(parents=True, exist_ok=True)
         self._init_db()
     
+    def _connect(self) -> sqlite3.Connection:
+        """Open a connection that waits out other writers instead of failing"""
+        conn = sqlite3.connect(self.db_path, timeout=BUSY_TIMEOUT_MS / 1000)
+        conn.execute(f"PRAGMA busy_timeout = {BUSY_TIMEOUT_MS}")
+        return conn
+    
     def _init_db(self) -> None:
         """Initialize database schema"""
-        with sqlite3.connect(self.db_path) as conn:
+        with self._connect() as conn:
             cursor = conn.cursor()
             
+            # Each method opens its own connection; WAL lets readers (e.g.
+            # background tasks reading preferences) run during a write.
+            # The mode is persistent, so this only needs doing once per file.
+            cursor.execute("PRAGMA journal_mode=WAL")
+            
             # User profile table
             cursor.execute("""
                 CREATE TABLE IF NOT EXISTS user_profile (
@@ -90,7 +316,17 @@ class MemoryStore:
                 )
             """)
             
-            # Memories table
+            # Labeled preference snapshots (JSON of category/key -> value)
+            cursor.execute("""
+                CREATE TABLE IF NOT EXISTS preference_snapshots (
+                    id INTEGER PRIMARY KEY,
+                    label TEXT NOT NULL UNIQUE,
+                    data TEXT NOT NULL,
+                    created_at TIMESTAMP DEFAULT CURRENT_TIMESTAMP
+                )
+            """)
+            
+            # Memories table (newer columns are added by MIGRATIONS)
             cursor.execute("""
                 CREATE TABLE IF NOT EXISTS memories (
                     id INTEGER PRIMARY KEY,
@@ -113,12 +349,75 @@ class MemoryStore:
             """)
             
             conn.commit()
+            
+            self._run_migrations(conn)
+    
+    # ========== Schema Migrations ==========
+    
+    def _run_migrations(self, conn: sqlite3.Connection) -> None:
+        """Bring the schema up to date, one transaction per migration"""
+        cursor = conn.cursor()
+        cursor.execute("PRAGMA user_version")
+        version = cursor.fetchone()[0]
+        
+        for target, description, method in self.MIGRATIONS:
+            if target <= version:
+                continue
+            logger.info(f"Migrating memory database to v{target}: {description}")
+            cursor.execute("BEGIN")
+            try:
+                getattr(self, method)(cursor)
+                cursor.execute(f"PRAGMA user_version = {target}")
+                conn.commit()
+            except Exception:
+                conn.rollback()
+                raise
+            version = target
+    
+    def _memory_columns(self, cursor: sqlite3.Cursor) -> set:
+        cursor.execute("PRAGMA table_info(memories)")
+        return {row[1] for row in cursor.fetchall()}
+    
+    def _migrate_memory_embeddings(self, cursor: sqlite3.Cursor) -> None:
+        if "embedding" not in self._memory_columns(cursor):
+            cursor.execute("ALTER TABLE memories ADD COLUMN embedding BLOB")
+    
+    def _migrate_memory_fts(self, cursor: sqlite3.Cursor) -> None:
+        # Full-text index over memory content, kept in sync by triggers
+        cursor.execute("""
+            CREATE VIRTUAL TABLE IF NOT EXISTS memories_fts
+            USING fts5(content, content='memories', content_rowid='id')
+        """)
+        cursor.execute("""
+            CREATE TRIGGER IF NOT EXISTS memories_fts_insert AFTER INSERT ON memories BEGIN
+                INSERT INTO memories_fts(rowid, content) VALUES (new.id, new.content);
+            END
+        """)
+        cursor.execute("""
+            CREATE TRIGGER IF NOT EXISTS memories_fts_delete AFTER DELETE ON memories BEGIN
+                INSERT INTO memories_fts(memories_fts, rowid, content)
+                VALUES ('delete', old.id, old.content);
+            END
+        """)
+        cursor.execute("""
+            CREATE TRIGGER IF NOT EXISTS memories_fts_update AFTER UPDATE OF content ON memories BEGIN
+                INSERT INTO memories_fts(memories_fts, rowid, content)
+                VALUES ('delete', old.id, old.content);
+                INSERT INTO memories_fts(rowid, content) VALUES (new.id, new.content);
+            END
+        """)
+        # Index memories stored before full-text search existed
+        cursor.execute("INSERT INTO memories_fts(memories_fts) VALUES ('rebuild')")
+    
+    def _migrate_memory_expiry(self, cursor: sqlite3.Cursor) -> None:
+        if "expires_at" not in self._memory_columns(cursor):
+            cursor.execute("ALTER TABLE memories ADD COLUMN expires_at TIMESTAMP")
     
     # ========== User Profile Methods ==========
     
     def get_user_profile(self) -> UserProfile:
         """Retrieve the user profile"""
-        with sqlite3.connect(self.db_path) as conn:
+        with self._connect() as conn:
             cursor = conn.cursor()
             cursor.execute("SELECT name, facts, created_at, updated_at FROM user_profile LIMIT 1")
             row = cursor.fetchone()
@@ -135,7 +434,7 @@ class MemoryStore:
     
     def save_user_profile(self, profile: UserProfile) -> None:
         """Save or update the user profile"""
-        with sqlite3.connect(self.db_path) as conn:
+        with self._connect() as conn:
             cursor = conn.cursor()
             facts_json = json.dumps(profile.facts)
             
@@ -169,11 +468,51 @@ class MemoryStore:
             profile.facts.append(fact)
             self.save_user_profile(profile)
     
+    def remove_user_fact(self, fact: str) -> bool:
+        """Remove a fact about the user (exact match), returning whether it existed"""
+        profile = self.get_user_profile()
+        if fact not in profile.facts:
+            return False
+        profile.facts = [f for f in profile.facts if f != fact]
+        self.save_user_profile(profile)
+        return True
+    
+    def clear_user_facts(self) -> None:
+        """Remove all facts about the user, keeping their name"""
+        profile = self.get_user_profile()
+        profile.facts = []
+        self.save_user_profile(profile)
+    
     # ========== Preference Methods ==========
     
+    def _encrypt_value(self, category: str, value: str) -> str:
+        """Encrypt the value if its category is secret"""
+        if category not in self.secret_categories:
+            return value
+        return _ENCRYPTED_PREFIX + self._cipher.encrypt(value.encode()).decode()
+    
+    def _decrypt_value(self, value: str) -> str:
+        """
+        Decrypt a stored value; plaintext values pass through.
+        
+        If the key is unavailable or wrong the stored token is returned
+        as-is rather than failing the whole read.
+        """
+        if not value.startswith(_ENCRYPTED_PREFIX):
+            return value
+        try:
+            if self._cipher is None:
+                self._cipher = keyring_cipher()
+            token = value[len(_ENCRYPTED_PREFIX):].encode()
+            return self._cipher.decrypt(token).decode()
+        except Exception as e:  # missing deps, keychain errors, InvalidToken
+            logger.warning(f"Could not decrypt preference value: {type(e).__name__} {e}")
+            return value
+    
     def set_preference(self, category: str, key: str, value: str) -> None:
-        """Set or update a preference"""
-        with sqlite3.connect(self.db_path) as conn:
+        """Set or update a preference (encrypted if the category is secret)"""
+        value = self._encrypt_value(category, value)
+        with self._connect() as conn:
             cursor = conn.cursor()
             cursor.execute("""
                 INSERT INTO preferences (category, key, value)
@@ -181,21 +520,42 @@ class MemoryStore:
                 ON CONFLICT(category, key) DO UPDATE SET value = ?
             """, (category, key, value, value))
             conn.commit()
+        self.preferences_version += 1
+    
+    def set_preferences(self, items: List[Tuple[str, str, str]]) -> None:
+        """
+        Set several (category, key, value) preferences in one transaction.
+        
+        Either every pair is written or, if any write fails, none are.
+        """
+        items = [
+            (category, key, self._encrypt_value(category, value))
+            for category, key, value in items
+        ]
+        with self._connect() as conn:
+            cursor = conn.cursor()
+            cursor.executemany("""
+                INSERT INTO preferences (category, key, value)
+                VALUES (?, ?, ?)
+                ON CONFLICT(category, key) DO UPDATE SET value = excluded.value
+            """, items)
+            conn.commit()
+        self.preferences_version += 1
     
     def get_preference(self, category: str, key: str) -> Optional[str]:
         """Get a specific preference"""
-        with sqlite3.connect(self.db_path) as conn:
+        with self._connect() as conn:
             cursor = conn.cursor()
             cursor.execute(
                 "SELECT value FROM preferences WHERE category = ? AND key = ?",
                 (category, key)
             )
             row = cursor.fetchone()
-            return row[0] if row else None
+            return self._decrypt_value(row[0]) if row else None
     
     def get_all_preferences(self) -> List[Preference]:
         """Get all stored preferences"""
-        with sqlite3.connect(self.db_path) as conn:
+        with self._connect() as conn:
             cursor = conn.cursor()
             cursor.execute("SELECT category, key, value, created_at FROM preferences")
             rows = cursor.fetchall()
@@ -204,70 +564,372 @@ class MemoryStore:
                 Preference(
                     category=row[0],
                     key=row[1],
-                    value=row[2],
+                    value=self._decrypt_value(row[2]),
                     created_at=datetime.fromisoformat(row[3]) if row[3] else None,
                 )
                 for row in rows
             ]
     
+    def get_preferences_by_category(self, category: str) -> List[Preference]:
+        """Get the preferences in one category, ordered by key"""
+        with self._connect() as conn:
+            cursor = conn.cursor()
+            cursor.execute(
+                "SELECT category, key, value, created_at FROM preferences WHERE category = ? ORDER BY key",
+                (category,)
+            )
+            
+            return [
+                Preference(
+                    category=row[0],
+                    key=row[1],
+                    value=self._decrypt_value(row[2]),
+                    created_at=datetime.fromisoformat(row[3]) if row[3] else None,
+                )
+                for row in cursor.fetchall()
+            ]
+    
+    def delete_preference(self, category: str, key: str) -> None:
+        """Delete a preference (no-op if it isn't set)"""
+        with self._connect() as conn:
+            cursor = conn.cursor()
+            cursor.execute(
+                "DELETE FROM preferences WHERE category = ? AND key = ?",
+                (category, key)
+            )
+            conn.commit()
+        self.preferences_version += 1
+    
+    def find_conflicting_preferences(
+        self,
+        aliases: Optional[Dict[str, List[str]]] = None
+    ) -> List[dict]:
+        """
+        Find preferences with the same meaning stored in several categories.
+        
+        Only keys in the alias map (defaults to PREFERENCE_ALIASES) are
+        checked, matched case-insensitively, since the same key name can
+        legitimately mean different things in different categories. Each
+        conflict is {"key", "entries", "values_differ"}, where entries
+        lists every {"category", "key", "value"} found.
+        """
+        aliases = self.PREFERENCE_ALIASES if aliases is None else aliases
+        canonical = {
+            alias.lower(): name
+            for name, keys in aliases.items()
+            for alias in keys
+        }
+        
+        groups: Dict[str, List[Preference]] = {}
+        for pref in self.get_all_preferences():
+            name = canonical.get(pref.key.lower())
+            if name:
+                groups.setdefault(name, []).append(pref)
+        
+        conflicts = []
+        for name, prefs in sorted(groups.items()):
+            if len({pref.category for pref in prefs}) < 2:
+                continue
+            conflicts.append({
+                "key": name,
+                "entries": [
+                    {"category": pref.category, "key": pref.key, "value": pref.value}
+                    for pref in prefs
+                ],
+                "values_differ": len({pref.value for pref in prefs}) > 1,
+            })
+        return conflicts
+    
+    def snapshot_preferences(self, label: str) -> int:
+        """
+        Store a labeled snapshot of all preferences.
+        
+        Values are copied as stored, so secret preferences stay encrypted
+        in the snapshot. Re-using a label overwrites the earlier snapshot.
+        Returns the number of preferences captured.
+        """
+        with self._connect() as conn:
+            cursor = conn.cursor()
+            cursor.execute("SELECT category, key, value FROM preferences")
+            data = {f"{category}/{key}": value for category, key, value in cursor.fetchall()}
+            cursor.execute("""
+                INSERT INTO preference_snapshots (label, data)
+                VALUES (?, ?)
+                ON CONFLICT(label) DO UPDATE SET
+                    data = excluded.data, created_at = CURRENT_TIMESTAMP
+            """, (label, json.dumps(data)))
+            conn.commit()
+        return len(data)
+    
+    def _get_preference_snapshot(self, label: str) -> dict:
+        with self._connect() as conn:
+            cursor = conn.cursor()
+            cursor.execute(
+                "SELECT data FROM preference_snapshots WHERE label = ?", (label,)
+            )
+            row = cursor.fetchone()
+        if not row:
+            raise ValueError(f"No preference snapshot named '{label}'")
+        return json.loads(row[0])
+    
+    def diff_preferences(self, label_a: str, label_b: str) -> dict:
+        """
+        Compare two preference snapshots.
+        
+        Keys are "category/key". Returns {"added": {key: value},
+        "removed": {key: value}, "changed": {key: [old, new]}} going
+        from label_a to label_b, with secret values decrypted.
+        """
+        before = {
+            k: self._decrypt_value(v) for k, v in self._get_preference_snapshot(label_a).items()
+        }
+        after = {
+            k: self._decrypt_value(v) for k, v in self._get_preference_snapshot(label_b).items()
+        }
+        
+        return {
+            "added": {k: after[k] for k in sorted(after.keys() - before.keys())},
+            "removed": {k: before[k] for k in sorted(before.keys() - after.keys())},
+            "changed": {
+                k: [before[k], after[k]]
+                for k in sorted(before.keys() & after.keys())
+                if before[k] != after[k]
+            },
+        }
+    
     # ========== Memory Methods ==========
     
+    def _clamp_importance(self, importance: int) -> int:
+        """Clamp an importance value to the 1-10 scale"""
+        return max(self.MIN_IMPORTANCE, min(self.MAX_IMPORTANCE, int(importance)))
+    
+    def normalize_importance(self, importance: Optional[int]) -> int:
+        """Apply the default for unset (None/0) importance, then clamp to 1-10"""
+        if not importance:
+            return self.default_importance
+        return self._clamp_importance(importance)
+    
+    def set_importance_floor(self, category: str, floor: int) -> None:
+        """Set the minimum importance for a category, raising existing memories to it"""
+        floor = self._clamp_importance(floor)
+        self.set_preference(self.IMPORTANCE_FLOOR_CATEGORY, category, str(floor))
+        with self._connect() as conn:
+            conn.execute(
+                "UPDATE memories SET importance = ? WHERE category = ? AND importance < ?",
+                (floor, category, floor)
+            )
+            conn.commit()
+    
+    def get_importance_floors(self) -> Dict[str, int]:
+        """Get the importance floor for each category that has one"""
+        with self._connect() as conn:
+            cursor = conn.cursor()
+            cursor.execute(
+                "SELECT key, value FROM preferences WHERE category = ?",
+                (self.IMPORTANCE_FLOOR_CATEGORY,)
+            )
+            return {key: int(value) for key, value in cursor.fetchall()}
+    
+    def _apply_importance_floor(self, category: str, importance: int) -> int:
+        """Raise importance to the category's floor, if it has one"""
+        floor = self.get_preference(self.IMPORTANCE_FLOOR_CATEGORY, category)
+        return max(importance, int(floor)) if floor else importance
+    
     def add_memory(
         self, 
         content: str, 
         category: str = "general",
-        importance: int = 5
+        importance: Optional[int] = None
+    ) -> int:
+        """Store a new memory (importance is clamped to 1-10 and the category floor)"""
+        return self._insert_memory(content, category, importance)
+    
+    def add_memory_with_ttl(
+        self,
+        content: str,
+        category: str = "general",
+        importance: Optional[int] = None,
+        ttl_seconds: int = 86400
+    ) -> int:
+        """
+        Store a memory that expires after ttl_seconds.
+        
+        Expired memories are hidden from searches right away and deleted
+        by purge_expired_memories.
+        """
+        if ttl_seconds <= 0:
+            raise ValueError("ttl_seconds must be positive")
+        return self._insert_memory(content, category, importance, ttl_seconds)
+    
+    def _insert_memory(
+        self,
+        content: str,
+        category: str,
+        importance: Optional[int],
+        ttl_seconds: Optional[int] = None
     ) -> int:
-        """Store a new memory"""
-        with sqlite3.connect(self.db_path) as conn:
+        importance = self._apply_importance_floor(
+            category, self.normalize_importance(importance)
+        )
+        embedding = self._embed(content)
+        with self._connect() as conn:
             cursor = conn.cursor()
             cursor.execute("""
-                INSERT INTO memories (content, category, importance)
-                VALUES (?, ?, ?)
-            """, (content, category, importance))
+                INSERT INTO memories (content, category, importance, embedding, expires_at)
+                VALUES (?, ?, ?, ?, CASE WHEN ? IS NULL THEN NULL
+                                         ELSE datetime('now', '+' || ? || ' seconds') END)
+            """, (
+                content, category, importance,
+                _encode_embedding(embedding) if embedding else None,
+                ttl_seconds, ttl_seconds,
+            ))
             conn.commit()
             return cursor.lastrowid
     
+    def _embed(self, text: str) -> Optional[List[float]]:
+        """Embed text with the configured embedder, None if unavailable"""
+        if self.embedder is None:
+            return None
+        try:
+            return self.embedder(text)
+        except Exception as e:
+            logger.warning(f"Memory embedding failed, using keyword search: {e}")
+            return None
+    
+    def _semantic_search_rows(
+        self,
+        cursor: sqlite3.Cursor,
+        query_embedding: List[float],
+        query: str,
+        category: Optional[str],
+        half_life_days: float
+    ) -> List[tuple]:
+        """
+        Rank matching memories by cosine similarity times effective importance.
+        
+        Rows carry that product as a trailing score column; the similarity
+        alone decides whether a memory matches at all. Rows stored before
+        an embedder was configured have no embedding; they still match by
+        keyword and rank after the semantic hits.
+        """
+        cursor.execute(f"""
+            SELECT id, content, category, importance, created_at, last_accessed, embedding
+            FROM memories
+            WHERE {_NOT_EXPIRED} {"AND category = ?" if category else ""}
+            ORDER BY effective_importance(importance, last_accessed) DESC, created_at DESC
+        """, [category] if category else [])
+        
+        scored = []
+        keyword_rows = []
+        for row in cursor.fetchall():
+            if row[6] is not None:
+                similarity = _cosine_similarity(query_embedding, _decode_embedding(row[6]))
+                if similarity >= self.MIN_SIMILARITY:
+                    weight = effective_importance(row[3], row[5], half_life_days)
+                    scored.append(row[:6] + (similarity * weight,))
+            elif query.lower() in row[1].lower():
+                keyword_rows.append(row[:6] + (None,))
+        
+        # Stable sort keeps importance/recency order among equal scores
+        scored.sort(key=lambda row: row[6], reverse=True)
+        return scored + keyword_rows
+    
+    def _keyword_search_rows(
+        self,
+        cursor: sqlite3.Cursor,
+        query: str,
+        category: Optional[str],
+        limit: int,
+        offset: int
+    ) -> Tuple[List[tuple], int]:
+        """
+        Full-text search ranked by bm25 relevance times effective importance.
+        
+        bm25() is lower-is-better, so it's negated before weighting.
+        Returns one page of rows and the total match count.
+        """
+        where = f"""
+            FROM memories_fts
+            JOIN memories m ON m.id = memories_fts.rowid
+            WHERE memories_fts MATCH ?
+            AND (m.expires_at IS NULL OR m.expires_at > CURRENT_TIMESTAMP)
+            {"AND m.category = ?" if category else ""}
+        """
+        
+        def run(match: str) -> Tuple[List[tuple], int]:
+            params = [match, category] if category else [match]
+            cursor.execute(f"SELECT COUNT(*) {where}", params)
+            total = cursor.fetchone()[0]
+            cursor.execute(f"""
+                SELECT m.id, m.content, m.category, m.importance, m.created_at,
+                       m.last_accessed,
+                       -bm25(memories_fts) * effective_importance(m.importance, m.last_accessed)
+                           AS score
+                {where}
+                ORDER BY score DESC, m.created_at DESC
+                LIMIT ? OFFSET ?
+            """, params + [limit, offset])
+            return cursor.fetchall(), total
+        
+        match = _fts_query(query)
+        if not match:
+            return [], 0
+        try:
+            return run(match)
+        except sqlite3.OperationalError:
+            # Malformed FTS syntax in user input; retry as plain words
+            match = _fts_query(query.replace('"', " ").rstrip("* "))
+            return run(match) if match else ([], 0)
+    
+    def get_decay_half_life_days(self) -> float:
+        """Half-life for search importance decay (0 disables it)"""
+        value = self.get_preference(*self.DECAY_PREFERENCE)
+        try:
+            return float(value) if value is not None else self.DEFAULT_DECAY_HALF_LIFE_DAYS
+        except ValueError:
+            return self.DEFAULT_DECAY_HALF_LIFE_DAYS
+    
     def search_memories(
         self, 
         query: str, 
         category: Optional[str] = None,
-        limit: int = 10
-    ) -> List[Memory]:
-        """Search memories by keyword"""
-        with sqlite3.connect(self.db_path) as conn:
+        limit: int = 10,
+        offset: int = 0
+    ) -> PagedResult[Memory]:
+        """
+        Search memories semantically when an embedder is set, else by keyword.
+        
+        Keyword search supports prefix matches and "quoted phrases".
+        Results are ranked by relevance weighted by importance decayed
+        since last access (see decay_half_life_days), so memories that
+        haven't been recalled in a while sink. Returns one page of results,
+        best first, each with that score, plus the total number of matches.
+        """
+        query_embedding = self._embed(query)
+        half_life = self.get_decay_half_life_days()
+        
+        with self._connect() as conn:
+            # Not deterministic: the result depends on the current time
+            conn.create_function(
+                "effective_importance", 2,
+                lambda importance, last_accessed: effective_importance(
+                    importance, last_accessed, half_life
+                ),
+            )
             cursor = conn.cursor()
             
-            if category:
-                cursor.execute("""
-                    SELECT id, content, category, importance, created_at, last_accessed
-                    FROM memories 
-                    WHERE content LIKE ? AND category = ?
-                    ORDER BY importance DESC, created_at DESC
-                    LIMIT ?
-                """, (f"%{query}%", category, limit))
+            if query_embedding:
+                matches = self._semantic_search_rows(
+                    cursor, query_embedding, query, category, half_life
+                )
+                rows, total = matches[offset:offset + limit], len(matches)
             else:
-                cursor.execute("""
-                    SELECT id, content, category, importance, created_at, last_accessed
-                    FROM memories 
-                    WHERE content LIKE ?
-                    ORDER BY importance DESC, created_at DESC
-                    LIMIT ?
-                """, (f"%{query}%", limit))
-            
-            rows = cursor.fetchall()
+                rows, total = self._keyword_search_rows(cursor, query, category, limit, offset)
             
-            # Update last_accessed for returned memories
-            memory_ids = [row[0] for row in rows]
-            if memory_ids:
-                placeholders = ",".join("?" * len(memory_ids))
-                cursor.execute(f"""
-                    UPDATE memories SET last_accessed = CURRENT_TIMESTAMP
-                    WHERE id IN ({placeholders})
-                """, memory_ids)
-                conn.commit()
+            self._touch_memories(cursor, [row[0] for row in rows])
+            conn.commit()
             
-            return [
+            items = [
                 Memory(
                     id=row[0],
                     content=row[1],
@@ -275,22 +937,130 @@ class MemoryStore:
                     importance=row[3],
                     created_at=datetime.fromisoformat(row[4]) if row[4] else None,
                     last_accessed=datetime.fromisoformat(row[5]) if row[5] else None,
+                    score=row[6],
                 )
                 for row in rows
             ]
+            
+            return PagedResult(items=items, total=total, limit=limit, offset=offset)
     
-    def get_recent_memories(self, limit: int = 20) -> List[Memory]:
-        """Get most recent memories"""
-        with sqlite3.connect(self.db_path) as conn:
+    def global_search(
+        self,
+        query: str,
+        limit: int = 5,
+        interactions: Optional["InteractionStore"] = None
+    ) -> List[SearchResult]:
+        """
+        Search memories, preferences and (optionally) conversation turns.
+        
+        Each source contributes at most `limit` hits so one can't crowd
+        out the others. Results are ranked by match score; ties keep the
+        memory, preference, conversation source order. Values of secret
+        preferences are masked.
+        """
+        results = [
+            SearchResult("memory", mem.content, _match_score(mem.content, query), str(mem.id))
+            for mem in self.search_memories(query, limit=limit).items
+        ]
+        
+        with self._connect() as conn:
             cursor = conn.cursor()
             cursor.execute("""
+                SELECT category, key, value FROM preferences
+                WHERE key LIKE ? OR value LIKE ?
+                ORDER BY category, key
+                LIMIT ?
+            """, (f"%{query}%", f"%{query}%", limit))
+            for category, key, value in cursor.fetchall():
+                if category in self.secret_categories or value.startswith(_ENCRYPTED_PREFIX):
+                    # Secrets only match by key and never show their value
+                    value, score = SECRET_MASK, _match_score(key, query)
+                else:
+                    score = max(_match_score(key, query), _match_score(value, query))
+                results.append(SearchResult(
+                    "preference", f"{category}/{key}: {value}", score, f"{category}/{key}"
+                ))
+        
+        if interactions is not None:
+            for msg in interactions.search_messages(query, limit=limit):
+                results.append(SearchResult(
+                    "conversation", msg.content, _match_score(msg.content, query), str(msg.id)
+                ))
+        
+        results.sort(key=lambda r: r.score, reverse=True)
+        return results
+    
+    def _touch_memories(self, cursor: sqlite3.Cursor, memory_ids: List[int]):
+        """Mark memories as accessed now with a single UPDATE"""
+        if not memory_ids:
+            return
+        placeholders = ",".join("?" * len(memory_ids))
+        cursor.execute(f"""
+            UPDATE memories SET last_accessed = CURRENT_TIMESTAMP
+            WHERE id IN ({placeholders})
+        """, memory_ids)
+    
+    def get_recent_memories(self, limit: int = 20) -> List[Memory]:
+        """Get most recent memories, marking them as accessed"""
+        with self._connect() as conn:
+            cursor = conn.cursor()
+            cursor.execute(f"""
                 SELECT id, content, category, importance, created_at, last_accessed
                 FROM memories 
+                WHERE {_NOT_EXPIRED}
                 ORDER BY created_at DESC
                 LIMIT ?
             """, (limit,))
+            rows = cursor.fetchall()
+            
+            self._touch_memories(cursor, [row[0] for row in rows])
+            conn.commit()
             
             return [
+                Memory(
+                    id=row[0],
+                    content=row[1],
+                    category=row[2],
+                    importance=row[3],
+                    created_at=datetime.fromisoformat(row[4]) if row[4] else None,
+                    last_accessed=datetime.fromisoformat(row[5]) if row[5] else None,
+                )
+                for row in rows
+            ]
+    
+    def list_memories(
+        self,
+        category: Optional[str] = None,
+        limit: int = 20,
+        offset: int = 0
+    ) -> PagedResult[Memory]:
+        """
+        Browse memories newest-first, optionally filtered by category.
+        
+        Unlike search_memories this does no keyword matching. Expired
+        memories are left out of both the page and the total count, which
+        covers the whole category (or all memories) for paging.
+        """
+        where = f"WHERE {_NOT_EXPIRED}"
+        params = []
+        if category:
+            where += " AND category = ?"
+            params.append(category)
+        
+        with self._connect() as conn:
+            cursor = conn.cursor()
+            cursor.execute(f"SELECT COUNT(*) FROM memories {where}", params)
+            total = cursor.fetchone()[0]
+            
+            cursor.execute(f"""
+                SELECT id, content, category, importance, created_at, last_accessed
+                FROM memories 
+                {where}
+                ORDER BY created_at DESC, id DESC
+                LIMIT ? OFFSET ?
+            """, params + [limit, offset])
+            
+            items = [
                 Memory(
                     id=row[0],
                     content=row[1],
@@ -301,15 +1071,17 @@ class MemoryStore:
                 )
                 for row in cursor.fetchall()
             ]
+            
+            return PagedResult(items=items, total=total, limit=limit, offset=offset)
     
     def get_important_memories(self, min_importance: int = 7, limit: int = 10) -> List[Memory]:
         """Get high-importance memories"""
-        with sqlite3.connect(self.db_path) as conn:
+        with self._connect() as conn:
             cursor = conn.cursor()
-            cursor.execute("""
+            cursor.execute(f"""
                 SELECT id, content, category, importance, created_at, last_accessed
                 FROM memories 
-                WHERE importance >= ?
+                WHERE importance >= ? AND {_NOT_EXPIRED}
                 ORDER BY importance DESC, created_at DESC
                 LIMIT ?
             """, (min_importance, limit))
@@ -326,9 +1098,87 @@ class MemoryStore:
                 for row in cursor.fetchall()
             ]
     
+    def update_memory(
+        self,
+        memory_id: int,
+        content: Optional[str] = None,
+        category: Optional[str] = None,
+        importance: Optional[int] = None
+    ) -> None:
+        """
+        Edit a stored memory; fields left as None are unchanged.
+        
+        Importance is clamped to 1-10 and the category's floor, and
+        changed content is re-embedded.
+        Bumps last_accessed. Raises KeyError if the id doesn't exist.
+        """
+        with self._connect() as conn:
+            cursor = conn.cursor()
+            cursor.execute(
+                "SELECT content, category, importance, embedding FROM memories WHERE id = ?",
+                (memory_id,)
+            )
+            row = cursor.fetchone()
+            if not row:
+                raise KeyError(f"Memory {memory_id} not found")
+            
+            category = category if category is not None else row[1]
+            importance = self._apply_importance_floor(
+                category,
+                self._clamp_importance(importance) if importance is not None else row[2]
+            )
+            
+            embedding = row[3]
+            if content is not None and content != row[0]:
+                vector = self._embed(content)
+                embedding = _encode_embedding(vector) if vector else None
+            
+            cursor.execute("""
+                UPDATE memories
+                SET content = ?, category = ?, importance = ?, embedding = ?,
+                    last_accessed = CURRENT_TIMESTAMP
+                WHERE id = ?
+            """, (
+                content if content is not None else row[0],
+                category,
+                importance,
+                embedding,
+                memory_id,
+            ))
+            conn.commit()
+    
+    def prune_memories(self, below_importance: int) -> int:
+        """
+        Delete memories with importance below the given threshold.
+        
+        Memories in a category with an importance floor are never pruned,
+        whatever their stored importance. Returns the number deleted.
+        """
+        with self._connect() as conn:
+            cursor = conn.cursor()
+            cursor.execute("""
+                DELETE FROM memories
+                WHERE importance < ?
+                AND category NOT IN (
+                    SELECT key FROM preferences WHERE category = ?
+                )
+            """, (below_importance, self.IMPORTANCE_FLOOR_CATEGORY))
+            conn.commit()
+            return cursor.rowcount
+    
+    def purge_expired_memories(self) -> int:
+        """Delete memories past their expiry, returning how many were removed"""
+        with self._connect() as conn:
+            cursor = conn.cursor()
+            cursor.execute(
+                "DELETE FROM memories WHERE expires_at IS NOT NULL AND expires_at <= CURRENT_TIMESTAMP"
+            )
+            conn.commit()
+            return cursor.rowcount
+    
     def delete_memory(self, memory_id: int) -> bool:
-        """Delete a specific memory"""
-        with sqlite3.connect(self.db_path) as conn:
+        """Delete a specific memory, returning whether a row was removed"""
+        with self._connect() as conn:
             cursor = conn.cursor()
             cursor.execute("DELETE FROM memories WHERE id = ?", (memory_id,))
             conn.commit()
@@ -338,12 +1188,159 @@ class MemoryStore:
     
     def clear_all(self) -> None:
         """Clear all stored data (use with caution!)"""
-        with sqlite3.connect(self.db_path) as conn:
+        with self._connect() as conn:
             cursor = conn.cursor()
             cursor.execute("DELETE FROM user_profile")
             cursor.execute("DELETE FROM preferences")
+            cursor.execute("DELETE FROM preference_snapshots")
             cursor.execute("DELETE FROM memories")
             conn.commit()
+        self.preferences_version += 1
+    
+    # ========== Backup Methods ==========
+    
+    EXPORT_VERSION = 1
+    
+    def export_memory(self, path: str) -> Dict[str, int]:
+        """
+        Write the profile, preferences and memories to a JSON file.
+        
+        Embeddings are left out; they're recomputed on import. Secret
+        preferences are written decrypted, since the key is tied to this
+        machine's keychain, and marked "sensitive". Returns counts of what
+        was exported.
+        """
+        profile = self.get_user_profile()
+        with self._connect() as conn:
+            cursor = conn.cursor()
+            cursor.execute("SELECT category, key, value FROM preferences ORDER BY category, key")
+            preferences = [
+                {
+                    "category": c,
+                    "key": k,
+                    "value": self._decrypt_value(v),
+                    "sensitive": v.startswith(_ENCRYPTED_PREFIX),
+                }
+                for c, k, v in cursor.fetchall()
+            ]
+            cursor.execute("""
+                SELECT content, category, importance, created_at, expires_at
+                FROM memories ORDER BY id
+            """)
+            memories = [
+                {
+                    "content": content,
+                    "category": category,
+                    "importance": importance,
+                    "created_at": created_at,
+                    "expires_at": expires_at,
+                }
+                for content, category, importance, created_at, expires_at in cursor.fetchall()
+            ]
+        
+        document = {
+            "version": self.EXPORT_VERSION,
+            "exported_at": datetime.now().isoformat(),
+            "profile": {"name": profile.name, "facts": profile.facts},
+            "preferences": preferences,
+            "memories": memories,
+        }
+        out = Path(path).expanduser()
+        out.parent.mkdir(parents=True, exist_ok=True)
+        out.write_text(json.dumps(document, indent=2))
+        
+        return {
+            "facts": len(profile.facts),
+            "preferences": len(preferences),
+            "sensitive": sum(p["sensitive"] for p in preferences),
+            "memories": len(memories),
+        }
+    
+    def import_memory(self, path: str, merge: bool = False) -> Dict[str, int]:
+        """
+        Load a JSON export, replacing the current store or merging into it.
+        
+        On merge the existing name wins, facts are unioned, preferences
+        upsert by (category, key) and memories with identical content are
+        skipped. Preferences in secret categories are re-encrypted with this
+        store's key. Everything is applied in one transaction. Returns counts
+        of what was imported.
+        """
+        document = json.loads(Path(path).expanduser().read_text())
+        if document.get("version") != self.EXPORT_VERSION:
+            raise ValueError(f"Unsupported memory export version: {document.get('version')}")
+        
+        imported_profile = document.get("profile") or {}
+        profile = self.get_user_profile() if merge else UserProfile()
+        new_facts = [f for f in imported_profile.get("facts", []) if f not in profile.facts]
+        profile.facts.extend(new_facts)
+        profile.name = profile.name or imported_profile.get("name")
+        
+        with self._connect() as conn:
+            cursor = conn.cursor()
+            
+            if merge:
+                cursor.execute("SELECT content FROM memories")
+                existing = {row[0] for row in cursor.fetchall()}
+            else:
+                existing = set()
+                cursor.execute("DELETE FROM preferences")
+                cursor.execute("DELETE FROM memories")
+            
+            cursor.execute("DELETE FROM user_profile")
+            if profile.name or profile.facts:
+                cursor.execute(
+                    "INSERT INTO user_profile (name, facts) VALUES (?, ?)",
+                    (profile.name, json.dumps(profile.facts))
+                )
+            
+            preferences = document.get("preferences", [])
+            cursor.executemany("""
+                INSERT INTO preferences (category, key, value)
+                VALUES (?, ?, ?)
+                ON CONFLICT(category, key) DO UPDATE SET value = excluded.value
+            """, [
+                (
+                    p["category"],
+                    p["key"],
+                    # Older exports carry tokens that are already encrypted
+                    p["value"] if p["value"].startswith(_ENCRYPTED_PREFIX)
+                    else self._encrypt_value(p["category"], p["value"]),
+                )
+                for p in preferences
+            ])
+            
+            memory_count = 0
+            for mem in document.get("memories", []):
+                if mem["content"] in existing:
+                    continue
+                existing.add(mem["content"])
+                embedding = self._embed(mem["content"])
+                cursor.execute("""
+                    INSERT INTO memories
+                        (content, category, importance, created_at, expires_at, embedding)
+                    VALUES (?, ?, ?, COALESCE(?, CURRENT_TIMESTAMP), ?, ?)
+                """, (
+                    mem["content"],
+                    mem.get("category", "general"),
+                    self.normalize_importance(mem.get("importance")),
+                    mem.get("created_at"),
+                    mem.get("expires_at"),
+                    _encode_embedding(embedding) if embedding else None,
+                ))
+                memory_count += 1
+            
+            conn.commit()
+        self.preferences_version += 1
+        
+        return {
+            "facts": len(new_facts),
+            "preferences": len(preferences),
+            "memories": memory_count,
+        }
+    
+    # Preference aliases kept first when the context summary is trimmed
+    CONTEXT_LOCATION_PREFERENCES = ("city", "latitude", "longitude", "timezone")
     
     def get_context_summary(self) -> str:
         """
@@ -356,34 +1353,94 @@ class MemoryStore:
         preferences = self.get_all_preferences()
         important_memories = self.get_important_memories(min_importance=7, limit=5)
         
+        return self._render_context(
+            profile.name,
+            profile.facts[:5],        # Limit to 5 facts
+            preferences[:10],         # Limit to 10 preferences
+            important_memories,
+        )
+    
+    def get_context_summary_budgeted(self, max_chars: int) -> str:
+        """
+        Like get_context_summary, but trimmed to at most max_chars.
+        
+        Content is kept in priority order: name, location preferences,
+        important memories, facts, then other preferences. Anything that
+        doesn't fit is dropped.
+        """
+        profile = self.get_user_profile()
+        preferences = self.get_all_preferences()
+        important_memories = self.get_important_memories(min_importance=7, limit=5)
+        
+        location_keys = {
+            alias.lower()
+            for name in self.CONTEXT_LOCATION_PREFERENCES
+            for alias in self.PREFERENCE_ALIASES.get(name, [name])
+        }
+        location_prefs = [p for p in preferences if p.key.lower() in location_keys]
+        other_prefs = [p for p in preferences if p.key.lower() not in location_keys]
+        
+        name = None
+        facts: List[str] = []
+        prefs: List[Preference] = []
+        memories: List[Memory] = []
+        
+        def fits() -> bool:
+            return len(self._render_context(name, facts, prefs, memories)) <= max_chars
+        
+        if profile.name:
+            name = profile.name
+            if not fits():
+                name = None
+        
+        candidates = (
+            [(prefs, p) for p in location_prefs[:10]]
+            + [(memories, m) for m in important_memories]
+            + [(facts, f) for f in profile.facts[:5]]
+            + [(prefs, p) for p in other_prefs[:max(0, 10 - len(location_prefs))]]
+        )
+        for section, item in candidates:
+            section.append(item)
+            if not fits():
+                section.pop()
+        
+        return self._render_context(name, facts, prefs, memories)
+    
+    def _render_context(
+        self,
+        name: Optional[str],
+        facts: List[str],
+        preferences: List[Preference],
+        memories: List[Memory]
+    ) -> str:
         parts = []
         
         # User identity
-        if profile.name:
-            parts.append(f"User's name: {profile.name}")
+        if name:
+            parts.append(f"User's name: {name}")
         
-        if profile.facts:
+        if facts:
             parts.append("Known facts about user:")
-            for fact in profile.facts[:5]:  # Limit to 5 facts
+            for fact in facts:
                 parts.append(f"  - {fact}")
         
         # Preferences
         if preferences:
             parts.append("\nUser preferences:")
-            for pref in preferences[:10]:  # Limit to 10 preferences
+            for pref in preferences:
                 parts.append(f"  - {pref.category}/{pref.key}: {pref.value}")
         
         # Important memories
-        if important_memories:
+        if memories:
             parts.append("\nImportant context:")
-            for mem in important_memories:
+            for mem in memories:
                 parts.append(f"  - {mem.content}")
         
         return "\n".join(parts) if parts else ""
     
     def get_stats(self) -> dict:
         """Get memory statistics"""
-        with sqlite3.connect(self.db_path) as conn:
+        with self._connect() as conn:
             cursor = conn.cursor()
             
             cursor.execute("SELECT COUNT(*) FROM memories")
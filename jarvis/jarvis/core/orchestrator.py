@@ -3,12 +3,14 @@
 from __future__ import annotations
 
 import asyncio
+from dataclasses import asdict
 from pathlib import Path
-from typing import AsyncIterator, Dict, List, Optional
+from typing import Any, AsyncIterator, Dict, List, Optional
 
 from jarvis.core.config import Settings, load_config
 from jarvis.core.interaction_store import InteractionStore
-from jarvis.core.llm_engine import LLMEngine, LLMResponse, Tool, ToolCall
+from jarvis.core.llm_engine import GenerationStats, LLMEngine, LLMResponse, Tool, ToolCall
+from jarvis.core.selftest import DEFAULT_TIMEOUT, run_selftest
 from jarvis.core.stt_engine import STTEngine
 from jarvis.core.tts_engine import TTSEngine
 from jarvis.core.vision_engine import VisionEngine
@@ -47,6 +49,18 @@ class JARVISOrchestrator:
         # Initialize interaction logging
         self.interaction_store = InteractionStore()
         self.current_conversation_id: Optional[int] = None
+        
+        # Set by cancel_generation to stop the active stream_chat
+        self._streaming = False
+        self._stream_cancelled = False
+        
+        self._warmup_task: Optional[asyncio.Task] = None
+        
+        # Token counts/timing of the most recent response, if the LLM reports them
+        self.last_generation_stats: Optional[GenerationStats] = None
+    
+    # Preference holding the primary model chosen with switch_model
+    MODEL_PREFERENCE = ("llm", "primary_model")
     
     async def initialize(self) -> None:
         """Initialize all components based on configuration"""
@@ -72,16 +86,67 @@ class JARVISOrchestrator:
         if self.settings.memory.enabled:
             from jarvis.integrations.memory_module import MemoryIntegration
             self.memory_integration = MemoryIntegration(
-                db_path=self.settings.memory.db_path
+                db_path=self.settings.memory.db_path,
+                default_importance=self.settings.memory.default_importance,
+                embedding_model=self.settings.memory.embedding_model,
+                ollama_host=self.settings.ollama_host,
+                secret_categories=self.settings.memory.secret_categories,
+                context_max_chars=self.settings.memory.context_max_chars,
             )
             await self.memory_integration.setup()
             self.integrations["memory"] = self.memory_integration
+            
+            # Use the model last picked with switch_model, if any
+            saved_model = self.memory_integration.memory.get_preference(*self.MODEL_PREFERENCE)
+            if saved_model:
+                self._set_primary_model(saved_model)
         
         # Initialize integrations
         await self._init_integrations()
         
         self._initialized = True
     
+    def start_warmup(self) -> None:
+        """
+        Load the primary model in the background, if llm.warmup is on.
+        
+        Only long-lived entry points (the UI and voice loop) call this, after
+        initialize(); one-shot CLI commands would pay for a model load they
+        never use.
+        """
+        if self.settings.llm.warmup:
+            self._warmup_task = asyncio.create_task(self.llm.warmup())
+    
+    def _set_primary_model(self, model: str) -> None:
+        self.settings.llm.primary_model = model
+        self.llm.primary_model = model
+    
+    async def list_models(self) -> List[str]:
+        """Models installed in Ollama"""
+        await self.initialize()
+        return await self.llm.list_models()
+    
+    async def switch_model(self, model: str) -> str:
+        """
+        Make an installed model the primary model and remember it across restarts.
+        
+        A bare name matches its ":latest" tag. Returns the resolved model
+        name; raises ValueError if it isn't installed.
+        """
+        installed = await self.list_models()
+        if model not in installed and f"{model}:latest" in installed:
+            model = f"{model}:latest"
+        if model not in installed:
+            raise ValueError(f"Model '{model}' is not installed in Ollama")
+        
+        self._set_primary_model(model)
+        if self.memory_integration:
+            self.memory_integration.memory.set_preference(*self.MODEL_PREFERENCE, model)
+        if self._warmup_task is not None:
+            # Long-lived session that warmed the old model; load the new one
+            self.start_warmup()
+        return model
+    
     async def _init_llm(self) -> LLMEngine:
         """Initialize LLM engine based on config"""
         from jarvis.providers.llm import OllamaProvider
@@ -93,6 +158,16 @@ class JARVISOrchestrator:
             host=self.settings.ollama_host,
             temperature=self.settings.llm.temperature,
             max_tokens=self.settings.llm.max_tokens,
+            top_p=self.settings.llm.top_p,
+            top_k=self.settings.llm.top_k,
+            seed=self.settings.llm.seed,
+            context_size=self.settings.llm.context_size,
+            gpu_layers=self.settings.llm.gpu_layers,
+            repeat_penalty=self.settings.llm.repeat_penalty,
+            repeat_last_n=self.settings.llm.repeat_last_n,
+            frequency_penalty=self.settings.llm.frequency_penalty,
+            presence_penalty=self.settings.llm.presence_penalty,
+            stop=self.settings.llm.stop,
         )
     
     async def _init_tts(self) -> TTSEngine:
@@ -252,10 +327,12 @@ class JARVISOrchestrator:
                         from jarvis.agents.connectors.wmata_connector import WMATAConnector
                         
                         api_key = prov.get('api_key', '') if isinstance(prov, dict) else getattr(prov, 'api_key', '')
+                        base_url = prov.get('base_url') if isinstance(prov, dict) else getattr(prov, 'base_url', None)
                         config = ConnectorConfig(
                             name='wmata',
                             connector_type='wmata',
                             api_key=api_key,
+                            extra={'base_url': base_url} if base_url else {},
                         )
                         transport_agent.register_connector(WMATAConnector(config))
                     
@@ -326,11 +403,14 @@ class JARVISOrchestrator:
             )
             
             units = getattr(weather_config, 'units', 'imperial')
+            extra = {'units': units}
+            if getattr(weather_config, 'base_url', None):
+                extra['base_url'] = weather_config.base_url
             
             config = ConnectorConfig(
                 name='weather.gov',
                 connector_type='weather',
-                extra={'units': units},
+                extra=extra,
             )
             weather_agent.register_connector(WeatherConnector(config))
             self.agent_coordinator.register_agent(weather_agent)
@@ -344,10 +424,12 @@ class JARVISOrchestrator:
             flight_agent = FlightAgent()
             
             api_key = getattr(flight_config, 'api_key', '')
+            opensky_base_url = getattr(flight_config, 'opensky_base_url', None)
             config = ConnectorConfig(
                 name='aviationstack',
                 connector_type='flight',
                 api_key=api_key,
+                extra={'opensky_base_url': opensky_base_url} if opensky_base_url else {},
             )
             flight_agent.register_connector(FlightConnector(config))
             self.agent_coordinator.register_agent(flight_agent)
@@ -372,7 +454,7 @@ class JARVISOrchestrator:
         # Setup all agents
         await self.agent_coordinator.setup()
     
-    def _get_system_prompt(self) -> str:
+    async def _get_system_prompt(self) -> str:
         """
         Build the system prompt with memory context.
         
@@ -415,7 +497,10 @@ Keep it crisp. You're JARVIS."""
 
         # Add memory context if available
         if self.memory_integration:
-            memory_context = self.memory_integration.get_context_for_prompt()
+            # Same window the model is sent, so the budget follows its clamp
+            memory_context = self.memory_integration.get_context_for_prompt(
+                await self.llm.context_window() if self.llm else self.settings.llm.context_size
+            )
             if memory_context:
                 base_prompt += f"\n\n**User Context:**\n{memory_context}"
         
@@ -457,13 +542,19 @@ Keep it crisp. You're JARVIS."""
         
         return f"Unknown tool: {tool_call.name}"
     
-    async def chat(self, message: str, speak: bool = True) -> str:
+    async def chat(
+        self,
+        message: str,
+        speak: bool = True,
+        max_tokens: Optional[int] = None,
+    ) -> str:
         """
         Process a text message and optionally speak the response.
         
         Args:
             message: User's text message
             speak: Whether to speak the response via TTS
+            max_tokens: Completion length cap for this message (defaults to llm.max_tokens)
             
         Returns:
             JARVIS's text response
@@ -487,7 +578,7 @@ Keep it crisp. You're JARVIS."""
         tools = self.get_all_tools()
         
         # Get system prompt with memory context
-        system_prompt = self._get_system_prompt()
+        system_prompt = await self._get_system_prompt()
         
         # Query LLM
         response = await self.llm.reason(
@@ -495,6 +586,7 @@ Keep it crisp. You're JARVIS."""
             tools=tools if tools else None,
             system_prompt=system_prompt,
             conversation_history=self.conversation_history,
+            max_tokens=max_tokens,
         )
         
         # Handle tool calls
@@ -518,18 +610,16 @@ Keep it crisp. You're JARVIS."""
             follow_up = await self.llm.reason(
                 prompt=f"Tool results:\n{tool_context}\n\nProvide a natural response to the user based on these results.",
                 conversation_history=self.conversation_history,
+                max_tokens=max_tokens,
             )
             final_response = follow_up.content
+            self.last_generation_stats = follow_up.stats
         else:
             final_response = response.content
+            self.last_generation_stats = response.stats
         
         # Log assistant response
-        assistant_message_id = self.interaction_store.log_message(
-            conversation_id=self.current_conversation_id,
-            role="assistant",
-            content=final_response,
-            model=self.settings.llm.primary_model
-        )
+        assistant_message_id = self._log_assistant_message(final_response)
         
         # Update conversation history
         self.conversation_history.append({"role": "user", "content": message})
@@ -545,13 +635,23 @@ Keep it crisp. You're JARVIS."""
         
         return final_response
     
-    async def stream_chat(self, message: str, speak: bool = False) -> AsyncIterator[str]:
+    async def stream_chat(
+        self,
+        message: str,
+        speak: bool = False,
+        max_tokens: Optional[int] = None,
+    ) -> AsyncIterator[str]:
         """
         Stream a response token-by-token for real-time UI updates.
         
+        Stops early, keeping the partial response, if cancel_generation()
+        is called while streaming. If the response hit the max_tokens cap,
+        last_generation_stats.truncated is set afterwards.
+        
         Args:
             message: User's text message
             speak: Whether to queue TTS after completion
+            max_tokens: Completion length cap for this message (defaults to llm.max_tokens)
             
         Yields:
             Response tokens as they are generated
@@ -572,30 +672,38 @@ Keep it crisp. You're JARVIS."""
         )
         
         # Get system prompt with memory context
-        system_prompt = self._get_system_prompt()
+        system_prompt = await self._get_system_prompt()
         
         # Track the full response for history
         full_response = []
         
         # Stream from LLM with system prompt and history
-        async for token in self.llm.stream(
+        tokens = self.llm.stream(
             prompt=message,
             system_prompt=system_prompt,
             conversation_history=self.conversation_history,
-        ):
-            full_response.append(token)
-            yield token
+            max_tokens=max_tokens,
+        )
+        self._streaming = True
+        self._stream_cancelled = False
+        try:
+            async for token in tokens:
+                if self._stream_cancelled:
+                    break
+                full_response.append(token)
+                yield token
+        finally:
+            # Closing the generator also closes the request to the model
+            await tokens.aclose()
+            self._streaming = False
+        cancelled = self._stream_cancelled
         
         # Build final response
         final_response = "".join(full_response)
         
         # Log assistant response
-        assistant_message_id = self.interaction_store.log_message(
-            conversation_id=self.current_conversation_id,
-            role="assistant",
-            content=final_response,
-            model=self.settings.llm.primary_model
-        )
+        self.last_generation_stats = self.llm.last_stream_stats
+        assistant_message_id = self._log_assistant_message(final_response)
         
         # Update conversation history
         self.conversation_history.append({"role": "user", "content": message})
@@ -606,8 +714,27 @@ Keep it crisp. You're JARVIS."""
             self.conversation_history = self.conversation_history[-40:]
         
         # Speak response after streaming completes
-        if speak and self.tts:
+        if speak and self.tts and not cancelled:
             await self.tts.speak(final_response)
+    
+    def _log_assistant_message(self, content: str) -> int:
+        """Log a response along with its generation stats, if known"""
+        stats = self.last_generation_stats
+        return self.interaction_store.log_message(
+            conversation_id=self.current_conversation_id,
+            role="assistant",
+            content=content,
+            tokens=stats.completion_tokens if stats else None,
+            model=self.settings.llm.primary_model,
+            metadata={"stats": asdict(stats)} if stats else None,
+        )
+    
+    def cancel_generation(self) -> bool:
+        """Stop the in-flight stream_chat; returns False if nothing is streaming"""
+        if not self._streaming:
+            return False
+        self._stream_cancelled = True
+        return True
 
 
     
@@ -671,6 +798,34 @@ Keep it crisp. You're JARVIS."""
             return "Vision capabilities not enabled."
         return await self.vision.analyze_camera(prompt)
     
+    async def run_integration_selftest(self, timeout: float = DEFAULT_TIMEOUT) -> Dict[str, Dict[str, Any]]:
+        """
+        Check every configured connector plus IP geolocation, concurrently.
+        
+        Each connector's probe() reuses its existing client, with its own
+        timeout. Returns {ok, latency_ms, error} per service, keyed by
+        connector name.
+        """
+        from jarvis.core.system_stats import LOCATION_UNAVAILABLE, SystemStats
+        
+        await self.initialize()
+        
+        probes = {}
+        if self.agent_coordinator:
+            for agent in self.agent_coordinator.agents.values():
+                for connector in agent.connectors:
+                    probes[connector.name] = connector.probe
+        
+        system_stats = SystemStats()
+        
+        async def _location() -> Optional[str]:
+            if await system_stats.get_location() == LOCATION_UNAVAILABLE:
+                return "IP geolocation unavailable"
+            return None
+        
+        probes["location"] = _location
+        return await run_selftest(probes, timeout)
+    
     async def health_check(self) -> dict:
         """Check health of all components"""
         await self.initialize()
@@ -0,0 +1,48 @@
+"""
+JARVIS Self-Test - Check that external services are reachable
+
+Runs a minimal probe per service concurrently, each with its own timeout,
+and reports {ok, latency_ms, error} for each. Used to diagnose a
+misconfigured API key or a blocked network before relying on JARVIS.
+"""
+
+from __future__ import annotations
+
+import asyncio
+import time
+from typing import Any, Awaitable, Callable, Dict, Optional
+
+# A probe returns None if the service responded as expected, else why not
+Probe = Callable[[], Awaitable[Optional[str]]]
+
+# Seconds before a single probe is reported as failed
+DEFAULT_TIMEOUT = 10.0
+
+
+async def check_service(probe: Probe, timeout: float = DEFAULT_TIMEOUT) -> Dict[str, Any]:
+    """Run one probe and report whether it passed, how long it took and why it failed"""
+    start = time.perf_counter()
+    error: Optional[str] = None
+    
+    try:
+        error = await asyncio.wait_for(probe(), timeout)
+    except asyncio.TimeoutError:
+        error = f"timed out after {timeout:g}s"
+    except Exception as e:
+        error = str(e) or type(e).__name__
+    
+    return {
+        "ok": error is None,
+        "latency_ms": round((time.perf_counter() - start) * 1000),
+        "error": error,
+    }
+
+
+async def run_selftest(
+    probes: Dict[str, Probe],
+    timeout: float = DEFAULT_TIMEOUT,
+) -> Dict[str, Dict[str, Any]]:
+    """Run all probes concurrently, keyed by service name"""
+    names = list(probes)
+    results = await asyncio.gather(*(check_service(probes[name], timeout) for name in names))
+    return dict(zip(names, results))
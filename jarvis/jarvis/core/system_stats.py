@@ -1,6 +1,9 @@
 """
 System Stats Module
-Retrieves system information (CPU, Memory, Disk, Network) and Location.
+Retrieves system information (CPU, Memory, Disk, Network, GPU) and Location.
+
+GPU stats are optional and NVIDIA-only for now: install the "gpu" extra
+(nvidia-ml-py). Without it, or without an NVIDIA GPU, GPU fields are None.
 """
 
 import psutil
@@ -8,16 +11,100 @@ import requests
 import socket
 import platform
 import asyncio
-from typing import Dict, Any
+import os
+import re
+import time
+from collections import deque
+from typing import Dict, Any, List, Optional
+
+# Optional imports
+try:
+    import pynvml
+    NVML_AVAILABLE = True
+except ImportError:
+    NVML_AVAILABLE = False
+
+# Upper bound for get_top_processes(limit)
+MAX_TOP_PROCESSES = 50
+
+# Returned by get_location when the IP lookup fails
+LOCATION_UNAVAILABLE = "Location Unavailable"
+
+# Readings kept by default for get_history (4 minutes at the 2 s default interval)
+DEFAULT_HISTORY_SIZE = 120
+
+# Stats refresh interval, stored as the ("sensor", "interval_ms") preference
+STATS_INTERVAL_PREFERENCE = ("sensor", "interval_ms")
+DEFAULT_STATS_INTERVAL_MS = 2000
+MIN_STATS_INTERVAL_MS = 500
+MAX_STATS_INTERVAL_MS = 60000
+
+
+def clamp_stats_interval(interval_ms: Any) -> int:
+    """Parse a refresh interval and clamp it to the allowed range (default if invalid)"""
+    try:
+        interval_ms = int(interval_ms)
+    except (TypeError, ValueError):
+        return DEFAULT_STATS_INTERVAL_MS
+    return max(MIN_STATS_INTERVAL_MS, min(MAX_STATS_INTERVAL_MS, interval_ms))
+
+
+# Alert thresholds in percent, overridable with ("sensor", "<metric>_alert_percent")
+# preferences, and how far below one a metric must fall to count as recovered
+DEFAULT_ALERT_THRESHOLDS = {"cpu": 90.0, "memory": 85.0}
+ALERT_HYSTERESIS = 5.0
+
+
+class ThresholdMonitor:
+    """
+    Turns stats readings into alerts when a metric crosses its threshold.
+    
+    Only transitions alert: a metric alerts when it rises to or above its
+    threshold and only counts as recovered once it drops `hysteresis`
+    points below it, so a value hovering at the boundary doesn't alert
+    every tick.
+    """
+
+    def __init__(self, hysteresis: float = ALERT_HYSTERESIS):
+        self.hysteresis = hysteresis
+        self._above: Dict[str, bool] = {}
+
+    def check(self, metric: str, value: float, threshold: float) -> Optional[Dict[str, Any]]:
+        """Returns {metric, value, threshold, severity} on a crossing, else None"""
+        above = self._above.get(metric, False)
+        if not above and value >= threshold:
+            severity = "warning"
+        elif above and value < threshold - self.hysteresis:
+            severity = "ok"
+        else:
+            return None
+        
+        self._above[metric] = not above
+        return {"metric": metric, "value": value, "threshold": threshold, "severity": severity}
+
 
 class SystemStats:
-    def __init__(self):
+    def __init__(self, history_size: int = DEFAULT_HISTORY_SIZE):
+        self._history: deque = deque(maxlen=history_size)
+        self._gpu_handle = self._init_gpu()
         self.last_net_io = psutil.net_io_counters()
+        self._last_disk_io = self._read_disk_io()
+        self._last_disk_time = time.monotonic()
+        self._last_nic_io = psutil.net_io_counters(pernic=True)
+        self._last_nic_time = time.monotonic()
+        # Prime CPU sampling: the first non-blocking cpu_percent() call
+        # always returns 0.0, so take it here instead of on first read.
+        psutil.cpu_percent(interval=None)
+        psutil.cpu_percent(interval=None, percpu=True)
 
     def get_cpu_info(self) -> float:
         """Returns overall CPU usage percentage"""
         return psutil.cpu_percent(interval=None)
 
+    def get_per_core_cpu(self) -> List[float]:
+        """Returns CPU usage percentage for each logical core"""
+        return psutil.cpu_percent(interval=None, percpu=True)
+
     def get_memory_info(self) -> Dict[str, Any]:
         """Returns memory usage statistics"""
         mem = psutil.virtual_memory()
@@ -27,9 +114,45 @@ class SystemStats:
             "available": mem.available,
             "percent": mem.percent,
             "used": mem.used,
-            "swap_percent": swap.percent
+            "swap_percent": swap.percent,
+            "swap_used": swap.used,
+            "swap_total": swap.total,
         }
 
+    def get_load_average(self) -> Optional[List[float]]:
+        """Returns 1/5/15-minute load average, or None where unavailable"""
+        try:
+            return list(os.getloadavg())
+        except (AttributeError, OSError):
+            return None
+
+    def _init_gpu(self) -> Optional[Any]:
+        """Initialize NVML once and cache the first GPU's handle"""
+        if not NVML_AVAILABLE:
+            return None
+        try:
+            pynvml.nvmlInit()
+            if pynvml.nvmlDeviceGetCount() == 0:
+                return None
+            return pynvml.nvmlDeviceGetHandleByIndex(0)
+        except Exception:
+            return None
+
+    def get_gpu_info(self) -> Dict[str, Optional[float]]:
+        """Returns GPU utilization and VRAM (NVIDIA only), None when unavailable"""
+        info = {"gpu_usage": None, "vram_used": None, "vram_total": None}
+        if self._gpu_handle is None:
+            return info
+        try:
+            util = pynvml.nvmlDeviceGetUtilizationRates(self._gpu_handle)
+            mem = pynvml.nvmlDeviceGetMemoryInfo(self._gpu_handle)
+            info["gpu_usage"] = float(util.gpu)
+            info["vram_used"] = mem.used
+            info["vram_total"] = mem.total
+        except Exception:
+            pass
+        return info
+
     def get_disk_info(self) -> Dict[str, Any]:
         """Returns disk usage statistics for the root partition"""
         disk = psutil.disk_usage('/')
@@ -40,16 +163,84 @@ class SystemStats:
             "percent": disk.percent
         }
 
-    def get_battery_info(self) -> Dict[str, Any]:
-        """Returns battery status if available"""
-        battery = psutil.sensors_battery()
-        if battery:
-            return {
-                "percent": battery.percent,
-                "power_plugged": battery.power_plugged,
-                "secsleft": battery.secsleft
-            }
-        return {"percent": 100, "power_plugged": True, "secsleft": 0}
+    def _read_disk_io(self) -> Dict[str, Any]:
+        """Per-device disk I/O counters, empty if unsupported"""
+        try:
+            return psutil.disk_io_counters(perdisk=True) or {}
+        except Exception:
+            return {}
+
+    @staticmethod
+    def _io_device_name(device: str, counters: Dict[str, Any]) -> str:
+        """Map a partition device to its I/O counter key (macOS counts per disk, not per slice)"""
+        name = os.path.basename(device)
+        if name not in counters:
+            name = re.sub(r"(s\d+)+$", "", name)  # disk3s1s1 -> disk3
+        return name
+
+    def get_disks(self) -> List[Dict[str, Any]]:
+        """
+        Returns usage and read/write throughput for each mounted disk.
+        
+        Throughput is bytes/sec since the previous call. Drives that are
+        removed between polls are skipped; new drives report 0 until the
+        next poll.
+        """
+        now = time.monotonic()
+        elapsed = max(now - self._last_disk_time, 1e-6)
+        curr_io = self._read_disk_io()
+        
+        disks = []
+        for part in psutil.disk_partitions(all=False):
+            try:
+                usage = psutil.disk_usage(part.mountpoint)
+            except (PermissionError, FileNotFoundError, OSError):
+                # Unmounted or inaccessible since partitions were listed
+                continue
+            
+            device = self._io_device_name(part.device, curr_io)
+            curr = curr_io.get(device)
+            prev = self._last_disk_io.get(device)
+            read_rate = write_rate = 0.0
+            if curr and prev:
+                read_rate = max(curr.read_bytes - prev.read_bytes, 0) / elapsed
+                write_rate = max(curr.write_bytes - prev.write_bytes, 0) / elapsed
+            
+            disks.append({
+                "mount_point": part.mountpoint,
+                "device": part.device,
+                "total": usage.total,
+                "available": usage.free,
+                "percent": usage.percent,
+                "read_bytes_per_sec": read_rate,
+                "write_bytes_per_sec": write_rate,
+            })
+        
+        self._last_disk_io = curr_io
+        self._last_disk_time = now
+        return disks
+
+    def get_battery_info(self) -> Optional[Dict[str, Any]]:
+        """
+        Returns battery status, or None on machines without a battery.
+        
+        secsleft is None while charging or when the OS can't estimate it.
+        """
+        try:
+            battery = psutil.sensors_battery()
+        except Exception:
+            battery = None
+        if battery is None:
+            return None
+        
+        # POWER_TIME_UNLIMITED / POWER_TIME_UNKNOWN are negative sentinels
+        secsleft = battery.secsleft if battery.secsleft >= 0 else None
+        
+        return {
+            "percent": battery.percent,
+            "power_plugged": battery.power_plugged,
+            "secsleft": secsleft
+        }
 
     def get_network_stats(self) -> Dict[str, float]:
         """Returns network bytes sent/received since last check"""
@@ -67,6 +258,79 @@ class SystemStats:
             "recv_kb": bytes_recv / 1024
         }
 
+    def get_networks(self, include_loopback: bool = False) -> List[Dict[str, Any]]:
+        """
+        Returns per-interface receive/transmit rates in bytes/sec since the
+        previous call. Interfaces that are down are listed with zero rates.
+        """
+        now = time.monotonic()
+        elapsed = max(now - self._last_nic_time, 1e-6)
+        curr_io = psutil.net_io_counters(pernic=True)
+        try:
+            if_stats = psutil.net_if_stats()
+        except Exception:
+            if_stats = {}
+        
+        networks = []
+        for name, curr in sorted(curr_io.items()):
+            is_loopback = name == "lo" or name.startswith("lo0") or name.lower().startswith("loopback")
+            if is_loopback and not include_loopback:
+                continue
+            
+            stats = if_stats.get(name)
+            is_up = bool(stats and stats.isup)
+            prev = self._last_nic_io.get(name)
+            rx_rate = tx_rate = 0.0
+            if is_up and prev:
+                rx_rate = max(curr.bytes_recv - prev.bytes_recv, 0) / elapsed
+                tx_rate = max(curr.bytes_sent - prev.bytes_sent, 0) / elapsed
+            
+            networks.append({
+                "name": name,
+                "is_up": is_up,
+                "received_per_sec": rx_rate,
+                "transmitted_per_sec": tx_rate,
+            })
+        
+        self._last_nic_io = curr_io
+        self._last_nic_time = now
+        return networks
+
+    def get_top_processes(
+        self,
+        limit: int = 10,
+        sort_by: str = "cpu",
+        exclude_self: bool = False,
+    ) -> List[Dict[str, Any]]:
+        """
+        Returns the busiest processes sorted by "cpu" or "memory".
+        
+        Walking the process table is expensive, so this is only done on
+        request, never from the periodic stats loop. CPU usage is measured
+        since the previous call (the first call reports 0 for new processes).
+        """
+        if sort_by not in ("cpu", "memory"):
+            raise ValueError(f"sort_by must be 'cpu' or 'memory', got '{sort_by}'")
+        limit = max(1, min(limit, MAX_TOP_PROCESSES))
+        own_pid = os.getpid()
+        
+        processes = []
+        for proc in psutil.process_iter(["pid", "name", "cpu_percent", "memory_info"]):
+            info = proc.info
+            if exclude_self and info["pid"] == own_pid:
+                continue
+            memory = info["memory_info"].rss if info["memory_info"] else 0
+            processes.append({
+                "pid": info["pid"],
+                "name": info["name"] or "",
+                "cpu_usage": info["cpu_percent"] or 0.0,
+                "memory": memory,
+            })
+        
+        key = "cpu_usage" if sort_by == "cpu" else "memory"
+        processes.sort(key=lambda p: p[key], reverse=True)
+        return processes[:limit]
+
     async def get_location(self) -> str:
         """Get approximate location based on IP"""
         try:
@@ -78,14 +342,35 @@ class SystemStats:
             region = data.get("region", "")
             return f"{city}, {region}"
         except Exception:
-            return "Location Unavailable"
+            return LOCATION_UNAVAILABLE
+
+    def record(self, reading: Dict[str, Any]) -> None:
+        """Add a reading to the rolling history, evicting the oldest when full"""
+        self._history.append(reading)
+
+    def get_history(self, seconds: float, now: Optional[float] = None) -> List[Dict[str, Any]]:
+        """Readings from the last `seconds`, oldest first"""
+        cutoff = (time.time() if now is None else now) - seconds
+        return [reading for reading in self._history if reading["timestamp"] >= cutoff]
 
     async def get_all_stats(self) -> Dict[str, Any]:
-        """Aggregate all stats for UI Consumption"""
-        return {
+        """
+        Aggregate all stats for UI Consumption.
+        
+        Each reading is also kept in the rolling history (see get_history).
+        """
+        reading = {
+            "timestamp": time.time(),
             "cpu": self.get_cpu_info(),
+            "per_core": self.get_per_core_cpu(),
+            "gpu": self.get_gpu_info(),
             "memory": self.get_memory_info(),
+            "load_average": self.get_load_average(),
             "disk": self.get_disk_info(),
+            "disks": self.get_disks(),
             "battery": self.get_battery_info(),
             "network": self.get_network_stats(),
+            "networks": self.get_networks(),
         }
+        self.record(reading)
+        return reading
@@ -0,0 +1,48 @@
+"""
+Version Info
+What JARVIS build is running, for bug reports and support requests.
+"""
+
+import importlib.util
+import platform
+import subprocess
+from pathlib import Path
+from typing import Any, Dict, Optional
+
+from jarvis import __version__
+
+# Optional extras from pyproject.toml -> modules they install
+OPTIONAL_EXTRAS = {
+    "gpu": ["pynvml"],
+    "secrets": ["keyring", "cryptography"],
+}
+
+
+def _git_commit() -> Optional[str]:
+    """Short commit hash when running from a git checkout, else None"""
+    try:
+        result = subprocess.run(
+            ["git", "rev-parse", "--short", "HEAD"],
+            cwd=Path(__file__).resolve().parent,
+            capture_output=True,
+            text=True,
+            timeout=5,
+        )
+    except (OSError, subprocess.SubprocessError):
+        return None
+    if result.returncode != 0:
+        return None
+    return result.stdout.strip() or None
+
+
+def get_version_info() -> Dict[str, Any]:
+    """Package version, git commit, Python version and installed extras"""
+    return {
+        "version": __version__,
+        "git_commit": _git_commit(),
+        "python": platform.python_version(),
+        "extras": {
+            extra: all(importlib.util.find_spec(module) for module in modules)
+            for extra, modules in OPTIONAL_EXTRAS.items()
+        },
+    }
@@ -10,12 +10,20 @@ Provides tools for the LLM to:
 
 from __future__ import annotations
 
-from typing import Any, List
+from typing import Any, List, Optional
 
 from jarvis.core.llm_engine import Tool
-from jarvis.core.memory_store import MemoryStore
+from jarvis.core.memory_store import MemoryStore, ollama_embedder
 from jarvis.integrations.base import Integration
 
+# Memory context budget when neither a cap nor the model's context size is known
+DEFAULT_CONTEXT_MAX_CHARS = 2000
+
+# Share of the model's context window given to memory context, and a rough
+# characters-per-token ratio to turn tokens into a character budget
+MEMORY_CONTEXT_SHARE = 0.125
+CHARS_PER_TOKEN = 4
+
 
 class MemoryIntegration(Integration):
     """
@@ -25,8 +33,23 @@ class MemoryIntegration(Integration):
     learn preferences, and maintain context across sessions.
     """
     
-    def __init__(self, db_path: str = None):
-        self.memory = MemoryStore(db_path)
+    def __init__(
+        self,
+        db_path: str = None,
+        default_importance: int = 5,
+        embedding_model: str = None,
+        ollama_host: str = None,
+        secret_categories: List[str] = None,
+        context_max_chars: int = None,
+    ):
+        self.context_max_chars = context_max_chars
+        embedder = ollama_embedder(embedding_model, ollama_host) if embedding_model else None
+        self.memory = MemoryStore(
+            db_path,
+            default_importance=default_importance,
+            embedder=embedder,
+            secret_categories=secret_categories,
+        )
     
     @property
     def name(self) -> str:
@@ -112,7 +135,7 @@ class MemoryIntegration(Integration):
         if tool_name == "remember_about_user":
             fact = params.get("fact", "")
             category = params.get("category", "general")
-            importance = params.get("importance", 5)
+            importance = self.memory.normalize_importance(params.get("importance"))
             
             # Store as both a memory and potentially as a user fact
             self.memory.add_memory(fact, category=category, importance=importance)
@@ -166,13 +189,16 @@ class MemoryIntegration(Integration):
             query = params.get("query", "")
             category = params.get("category")
             
-            memories = self.memory.search_memories(query, category=category)
+            found = self.memory.search_memories(query, category=category)
             
-            if not memories:
+            if not found.items:
                 return f"No memories found matching '{query}'"
             
-            results = [f"Found {len(memories)} memories:"]
-            for mem in memories:
+            if found.total > len(found.items):
+                results = [f"Top {len(found.items)} of {found.total} memories:"]
+            else:
+                results = [f"Found {found.total} memories:"]
+            for mem in found.items:
                 results.append(f"  - [{mem.category}] {mem.content}")
             
             return "\n".join(results)
@@ -192,6 +218,23 @@ class MemoryIntegration(Integration):
         except Exception:
             return False
     
-    def get_context_for_prompt(self) -> str:
-        """Get memory context to inject into system prompt"""
+    def context_budget(self, context_tokens: Optional[int] = None) -> int:
+        """
+        Character budget for memory context (0 = no cap).
+        
+        The configured context_max_chars wins; otherwise it's a share of
+        the model's context window in tokens, falling back to
+        DEFAULT_CONTEXT_MAX_CHARS when that isn't known either.
+        """
+        if self.context_max_chars is not None:
+            return self.context_max_chars
+        if context_tokens:
+            return int(context_tokens * MEMORY_CONTEXT_SHARE * CHARS_PER_TOKEN)
+        return DEFAULT_CONTEXT_MAX_CHARS
+    
+    def get_context_for_prompt(self, context_tokens: Optional[int] = None) -> str:
+        """Get memory context to inject into system prompt, trimmed to the budget if set"""
+        budget = self.context_budget(context_tokens)
+        if budget:
+            return self.memory.get_context_summary_budgeted(budget)
         return self.memory.get_context_summary()
@@ -3,10 +3,12 @@
 from __future__ import annotations
 
 import asyncio
+import json
+import time
 from typing import AsyncIterator, List, Optional, Dict
 import ollama
 
-from jarvis.core.llm_engine import LLMEngine, LLMResponse, Tool, ToolCall
+from jarvis.core.llm_engine import GenerationStats, LLMEngine, LLMResponse, Tool, ToolCall
 
 
 # Default JARVIS system prompt
@@ -30,6 +32,53 @@ DEFAULT_SYSTEM_PROMPT = """You are JARVIS, an advanced AI assistant inspired by
 
 Always respond naturally as if speaking out loud. Keep responses concise for voice output."""
 
+# Accepted (low, high) for each penalty option, inclusive. A repeat penalty
+# of 1.0 and frequency/presence penalties of 0 are neutral.
+PENALTY_RANGES = {
+    "repeat_penalty": (0.5, 2.0),
+    "frequency_penalty": (-2.0, 2.0),
+    "presence_penalty": (-2.0, 2.0),
+}
+
+# Rough prompt size estimate, and the completion room always left over
+CHARS_PER_TOKEN = 4
+MIN_COMPLETION_TOKENS = 256
+
+# Bytes per KV cache element (Ollama's default f16 cache)
+KV_CACHE_ELEMENT_BYTES = 2
+
+# Suggested context sizes are rounded down to a multiple of this many tokens
+CONTEXT_SIZE_STEP = 1024
+
+
+def estimate_prompt_tokens(messages: list[dict], tools: Optional[List[Dict]] = None) -> int:
+    """Approximate token count of a chat request (message text plus tool schemas)"""
+    chars = sum(len(str(message.get("content") or "")) for message in messages)
+    if tools:
+        chars += len(json.dumps(tools, default=str))
+    return chars // CHARS_PER_TOKEN
+
+
+def suggest_context_size(
+    kv_bytes_per_token: int,
+    memory_budget: int,
+    context_length: Optional[int] = None,
+) -> dict:
+    """
+    Largest context whose KV cache fits in memory_budget bytes.
+    
+    Rounded down to CONTEXT_SIZE_STEP tokens (but never below one step)
+    and capped at the model's trained context_length when known.
+    Returns {"context_size", "kv_cache_bytes"}.
+    """
+    if kv_bytes_per_token <= 0:
+        raise ValueError("KV cache size per token must be positive")
+    fits = memory_budget // kv_bytes_per_token
+    size = max(CONTEXT_SIZE_STEP, fits // CONTEXT_SIZE_STEP * CONTEXT_SIZE_STEP)
+    if context_length:
+        size = min(size, context_length)
+    return {"context_size": size, "kv_cache_bytes": size * kv_bytes_per_token}
+
 
 class OllamaProvider(LLMEngine):
     """
@@ -47,6 +96,16 @@ class OllamaProvider(LLMEngine):
         host: str = "http://localhost:11434",
         temperature: float = 0.7,
         max_tokens: int = 2048,
+        top_p: Optional[float] = None,
+        top_k: Optional[int] = None,
+        seed: Optional[int] = None,
+        context_size: Optional[int] = None,
+        gpu_layers: Optional[int] = None,
+        repeat_penalty: Optional[float] = None,
+        repeat_last_n: Optional[int] = None,
+        frequency_penalty: Optional[float] = None,
+        presence_penalty: Optional[float] = None,
+        stop: Optional[List[str]] = None,
     ):
         self.fast_model = fast_model
         self.primary_model = primary_model
@@ -54,7 +113,185 @@ class OllamaProvider(LLMEngine):
         self.host = host
         self.temperature = temperature
         self.max_tokens = max_tokens
+        self.top_p = top_p
+        self.top_k = top_k
+        self.seed = seed
+        self.context_size = context_size
+        self.gpu_layers = gpu_layers
+        self.repeat_penalty = repeat_penalty
+        self.repeat_last_n = repeat_last_n
+        self.frequency_penalty = frequency_penalty
+        self.presence_penalty = presence_penalty
+        self.stop = stop
+        self._validate_penalties()
         self._client = ollama.AsyncClient(host=host)
+        # Trained maximum context per model, resolved on first use
+        self._context_lengths: Dict[str, Optional[int]] = {}
+    
+    def _validate_penalties(self) -> None:
+        """Reject penalty settings Ollama would accept but that wreck the output"""
+        for name, (low, high) in PENALTY_RANGES.items():
+            value = getattr(self, name)
+            if value is not None and not low <= value <= high:
+                raise ValueError(f"llm.{name} must be between {low:g} and {high:g}, got {value:g}")
+        if self.repeat_last_n is not None and self.repeat_last_n < -1:
+            raise ValueError(
+                f"llm.repeat_last_n must be -1 (whole context), 0 (off) or more, got {self.repeat_last_n}"
+            )
+    
+    def _num_ctx(self, context_length: Optional[int] = None) -> Optional[int]:
+        """context_size clamped to the model's trained context_length when known"""
+        if self.context_size and context_length:
+            return min(self.context_size, context_length)
+        return self.context_size
+    
+    async def context_window(self) -> Optional[int]:
+        """The num_ctx sent for the primary model (None means Ollama's default)"""
+        return self._num_ctx(await self._trained_context_length(self.primary_model))
+    
+    def _options(
+        self,
+        max_tokens: Optional[int] = None,
+        context_length: Optional[int] = None,
+        prompt_tokens: int = 0,
+    ) -> dict:
+        """
+        Sampling options for Ollama; unset values use the model's defaults.
+        
+        max_tokens overrides the configured completion cap for one request.
+        context_size is clamped to the model's trained context_length when
+        known, since a window past it only costs memory. The prompt and
+        completion share the window (the trained length if context_size is
+        unset), so the completion cap is limited to what the estimated
+        prompt_tokens leave of it, but never below MIN_COMPLETION_TOKENS.
+        """
+        num_ctx = self._num_ctx(context_length)
+        num_predict = max_tokens or self.max_tokens
+        window = num_ctx or context_length
+        if window:
+            room = max(MIN_COMPLETION_TOKENS, window - prompt_tokens)
+            num_predict = min(num_predict, room)
+        options = {
+            "temperature": self.temperature,
+            "num_predict": num_predict,
+        }
+        for name in (
+            "top_p", "top_k", "seed",
+            "repeat_penalty", "repeat_last_n", "frequency_penalty", "presence_penalty",
+            "stop",
+        ):
+            value = getattr(self, name)
+            if value is not None:
+                options[name] = value
+        if num_ctx:
+            options["num_ctx"] = num_ctx
+        if self.gpu_layers is not None:
+            options["num_gpu"] = self.gpu_layers
+        return options
+    
+    async def _trained_context_length(self, model: str) -> Optional[int]:
+        """
+        The model's trained maximum context, looked up once per model.
+        
+        A failed lookup isn't cached and is retried next call.
+        """
+        if model not in self._context_lengths:
+            try:
+                info = await self.get_model_info(model)
+            except Exception as e:
+                print(f"Could not read the context length of {model}: {e}")
+                return None
+            self._context_lengths[model] = info.get("context_length")
+        return self._context_lengths[model]
+    
+    async def get_model_info(self, model: Optional[str] = None) -> dict:
+        """
+        Describe a model from Ollama's metadata and running state.
+        
+        Returns {"model", "installed": False} if Ollama doesn't have it.
+        Otherwise also family, parameter_size/parameter_count,
+        quantization, context_length (trained maximum), context_size
+        (configured, clamped to that maximum; None means Ollama's default),
+        embedding_length, rope_freq_base, kv_cache_bytes_per_token (None if
+        the metadata doesn't say), whether it's loaded and how much of it
+        sits in GPU memory (gpu_percent).
+        """
+        model = model or self.primary_model
+        try:
+            response = await self._client.show(model)
+        except ollama.ResponseError:
+            return {"model": model, "installed": False}
+        
+        details = response.get("details") or {}
+        info = getattr(response, "modelinfo", None) or response.get("model_info") or {}
+        
+        def metadata(suffix: str):
+            return next((value for key, value in info.items() if key.endswith(suffix)), None)
+        
+        context_length = metadata(".context_length")
+        context_size = self.context_size
+        if context_size and context_length and context_size > context_length:
+            context_size = context_length
+        
+        running = None
+        try:
+            for entry in (await self._client.ps()).get("models") or []:
+                if model in (entry.get("model"), entry.get("name")):
+                    running = entry
+                    break
+        except Exception:
+            pass  # Running state is best-effort
+        
+        # K and V per layer, each head_count_kv heads of key/value_length
+        block_count = metadata(".block_count")
+        head_count = metadata(".attention.head_count")
+        head_count_kv = metadata(".attention.head_count_kv") or head_count
+        if isinstance(head_count_kv, list):  # Per-layer counts on some architectures
+            head_count_kv = max(head_count_kv)
+        embedding_length = metadata(".embedding_length")
+        head_dim = embedding_length // head_count if embedding_length and head_count else None
+        key_length = metadata(".attention.key_length") or head_dim
+        value_length = metadata(".attention.value_length") or head_dim
+        kv_bytes_per_token = None
+        if block_count and head_count_kv and key_length and value_length:
+            kv_bytes_per_token = (
+                block_count * head_count_kv * (key_length + value_length) * KV_CACHE_ELEMENT_BYTES
+            )
+        
+        gpu_percent = None
+        if running and running.get("size"):
+            gpu_percent = round(100 * (running.get("size_vram") or 0) / running.get("size"))
+        
+        return {
+            "model": model,
+            "installed": True,
+            "family": details.get("family"),
+            "parameter_size": details.get("parameter_size"),
+            "parameter_count": info.get("general.parameter_count"),
+            "quantization": details.get("quantization_level"),
+            "context_length": context_length,
+            "context_size": context_size,
+            "embedding_length": embedding_length,
+            "rope_freq_base": metadata(".rope.freq_base"),
+            "kv_cache_bytes_per_token": kv_bytes_per_token,
+            "loaded": running is not None,
+            "gpu_percent": gpu_percent,
+        }
+    
+    async def suggest_context_size(self, memory_budget: int, model: Optional[str] = None) -> dict:
+        """
+        Suggest a context_size whose KV cache fits in memory_budget bytes.
+        
+        Returns {"context_size", "kv_cache_bytes"} capped at the model's
+        trained context, or an empty dict if the model is missing or its
+        metadata doesn't describe the attention layout.
+        """
+        info = await self.get_model_info(model)
+        if not info.get("kv_cache_bytes_per_token"):
+            return {}
+        return suggest_context_size(
+            info["kv_cache_bytes_per_token"], memory_budget, info.get("context_length")
+        )
     
     async def reason(
         self,
@@ -63,6 +300,7 @@ class OllamaProvider(LLMEngine):
         system_prompt: Optional[str] = None,
         conversation_history: Optional[list[dict]] = None,
         use_fast: bool = None,  # Override complexity detection
+        max_tokens: Optional[int] = None,
     ) -> LLMResponse:
         """Generate a response, with automatic fallback on failure"""
         
@@ -94,7 +332,7 @@ class OllamaProvider(LLMEngine):
         # Try selected model first
         try:
             response = await self._call_model(
-                selected_model, messages, ollama_tools
+                selected_model, messages, ollama_tools, max_tokens
             )
             return response
         except Exception as e:
@@ -103,7 +341,7 @@ class OllamaProvider(LLMEngine):
                 print(f"Fast model failed, trying primary: {e}")
                 try:
                     response = await self._call_model(
-                        self.primary_model, messages, ollama_tools
+                        self.primary_model, messages, ollama_tools, max_tokens
                     )
                     return response
                 except Exception as primary_error:
@@ -113,7 +351,7 @@ class OllamaProvider(LLMEngine):
             print(f"Selected model failed ({e}), falling back to {self.fallback_model}")
             try:
                 response = await self._call_model(
-                    self.fallback_model, messages, ollama_tools
+                    self.fallback_model, messages, ollama_tools, max_tokens
                 )
                 return response
             except Exception as fallback_error:
@@ -169,16 +407,19 @@ class OllamaProvider(LLMEngine):
         model: str,
         messages: list[dict],
         tools: Optional[List[Dict]] = None,
+        max_tokens: Optional[int] = None,
     ) -> LLMResponse:
         """Make the actual API call to Ollama"""
         
+        options = self._options(
+            max_tokens,
+            await self._trained_context_length(model),
+            estimate_prompt_tokens(messages, tools),
+        )
         kwargs = {
             "model": model,
             "messages": messages,
-            "options": {
-                "temperature": self.temperature,
-                "num_predict": self.max_tokens,
-            }
+            "options": options,
         }
         
         if tools:
@@ -186,6 +427,15 @@ class OllamaProvider(LLMEngine):
         
         response = await self._client.chat(**kwargs)
         
+        # Ollama silently drops the start of a prompt that overflows num_ctx
+        num_ctx = options.get("num_ctx")
+        prompt_tokens = getattr(response, "prompt_eval_count", None)
+        if num_ctx and prompt_tokens and prompt_tokens >= num_ctx:
+            print(
+                f"Prompt filled the {num_ctx}-token context window of {model}; "
+                "earlier content was truncated. Raise llm.context_size."
+            )
+        
         # Parse tool calls if present
         tool_calls = []
         if hasattr(response, "message") and hasattr(response.message, "tool_calls"):
@@ -201,7 +451,19 @@ class OllamaProvider(LLMEngine):
         return LLMResponse(
             content=content or "",
             tool_calls=tool_calls,
-            raw_response=response
+            raw_response=response,
+            stats=self._stats(response),
+        )
+    
+    @staticmethod
+    def _stats(response) -> GenerationStats:
+        """Read token counts and timing from a final Ollama response/chunk"""
+        total_ns = getattr(response, "total_duration", None)
+        return GenerationStats(
+            prompt_tokens=getattr(response, "prompt_eval_count", None),
+            completion_tokens=getattr(response, "eval_count", None),
+            generation_ms=total_ns / 1e6 if total_ns else None,
+            truncated=getattr(response, "done_reason", None) == "length",
         )
     
     async def stream(
@@ -209,6 +471,7 @@ class OllamaProvider(LLMEngine):
         prompt: str,
         system_prompt: Optional[str] = None,
         conversation_history: Optional[list[dict]] = None,
+        max_tokens: Optional[int] = None,
     ) -> AsyncIterator[str]:
         """Stream response tokens for real-time TTS"""
         
@@ -225,19 +488,22 @@ class OllamaProvider(LLMEngine):
         
         messages.append({"role": "user", "content": prompt})
 
-        
+        self.last_stream_stats = None
         try:
             stream = await self._client.chat(
                 model=self.primary_model,
                 messages=messages,
                 stream=True,
-                options={
-                    "temperature": self.temperature,
-                    "num_predict": self.max_tokens,
-                }
+                options=self._options(
+                    max_tokens,
+                    await self._trained_context_length(self.primary_model),
+                    estimate_prompt_tokens(messages),
+                ),
             )
             
             async for chunk in stream:
+                if getattr(chunk, "done", False):
+                    self.last_stream_stats = self._stats(chunk)
                 if hasattr(chunk, "message") and chunk.message.content:
                     yield chunk.message.content
                     
@@ -259,6 +525,29 @@ class OllamaProvider(LLMEngine):
         except Exception:
             return False
     
+    async def warmup(self, model: Optional[str] = None) -> None:
+        """
+        Load the model into memory so the first real request isn't slow.
+        
+        An empty prompt makes Ollama load the model without generating.
+        It's sent with the same num_ctx/num_gpu as chat, otherwise Ollama
+        loads the model with its defaults and reloads on the first request.
+        Failures are logged and otherwise ignored.
+        """
+        model = model or self.primary_model
+        start = time.monotonic()
+        try:
+            options = self._options(context_length=await self._trained_context_length(model))
+            await self._client.generate(model=model, prompt="", options=options)
+            print(f"Warmed up {model} in {time.monotonic() - start:.1f}s")
+        except Exception as e:
+            print(f"Warmup of {model} failed: {e}")
+    
+    async def list_models(self) -> List[str]:
+        """Names of the models installed in Ollama"""
+        models = await self._client.list()
+        return sorted(m.model for m in models.models)
+    
     async def ensure_model(self, model: str) -> bool:
         """Pull model if not available"""
         try:
@@ -5,14 +5,47 @@ from __future__ import annotations
 import asyncio
 import flet as ft
 from pathlib import Path
-from typing import Optional
+from typing import Any, Dict, List, Optional
 import datetime
 
 from jarvis.core.orchestrator import JARVISOrchestrator
-from jarvis.core.system_stats import SystemStats
+from jarvis.core.system_stats import (
+    DEFAULT_ALERT_THRESHOLDS,
+    MAX_STATS_INTERVAL_MS,
+    MIN_STATS_INTERVAL_MS,
+    STATS_INTERVAL_PREFERENCE,
+    SystemStats,
+    ThresholdMonitor,
+    clamp_stats_interval,
+)
 from jarvis.integrations.imessage import IMessageIntegration
 from jarvis.ui.components import Orb, StatRing, HexButton
 
+# While stats are paused, how often the loop checks whether to resume
+PAUSED_STATS_POLL_SECONDS = 5
+
+# Window shown by the CPU/memory sparklines, and the most points that fit the panel
+SPARKLINE_SECONDS = 60
+SPARKLINE_MAX_POINTS = 40
+
+
+def _sparkline(values: List[float], maximum: float = 100.0) -> str:
+    """Render values (0..maximum) as a row of block characters"""
+    blocks = "▁▂▃▄▅▆▇█"
+    return "".join(
+        blocks[min(int(max(v, 0) / maximum * len(blocks)), len(blocks) - 1)] for v in values
+    )
+
+
+def _format_bytes(num: float) -> str:
+    """Human-readable byte count (binary units)"""
+    for unit in ("B", "KB", "MB", "GB"):
+        if abs(num) < 1024:
+            return f"{num:.1f} {unit}"
+        num /= 1024
+    return f"{num:.1f} TB"
+
+
 class JarvisUI:
     def __init__(self, page: ft.Page, orchestrator: JARVISOrchestrator):
         self.page = page
@@ -20,16 +53,41 @@ class JarvisUI:
         self.orchestrator.conversation_history = []
         
         self.system_stats = SystemStats()
+        self.stat_alerts = ThresholdMonitor()
         self.imessage = IMessageIntegration()
         
         # State
         self.is_monitoring = True
+        self.stats_paused = False
+        self._stats_paused_by_user = False
+        self._stats_wake = asyncio.Event()  # Set to refresh stats before the next tick
+        self._stop_requested = False  # Stop pressed during the current response
+        self._stat_preferences: Dict[tuple, Optional[str]] = {}  # Cached; see _stat_preference
+        self._stat_preferences_version: Optional[int] = None
         
         # UI Refs
         self.orb = Orb()
         self.cpu_ring = StatRing("CPU", 0.0)
         self.mem_ring = StatRing("MEM", 0.0)
         self.batt_ring = StatRing("BATT", 0.0)
+        self.pause_button = ft.IconButton(
+            icon=ft.Icons.PAUSE,
+            icon_color="cyan",
+            icon_size=16,
+            tooltip="Pause stats",
+            on_click=self._toggle_stats_pause,
+        )
+        self.core_map = ft.Row(wrap=True, spacing=3, run_spacing=3)
+        self.sparklines = ft.Column(spacing=0)
+        self.stat_details = ft.Column(spacing=2)
+        self.interval_slider = ft.Slider(
+            min=MIN_STATS_INTERVAL_MS / 1000,
+            max=MAX_STATS_INTERVAL_MS / 1000,
+            divisions=(MAX_STATS_INTERVAL_MS - MIN_STATS_INTERVAL_MS) // 500,
+            value=self._stats_interval_ms() / 1000,
+            label="{value}s",
+            on_change_end=self._handle_interval_change,
+        )
         
         self.chat_list = ft.ListView(
             expand=True,
@@ -53,11 +111,11 @@ class JarvisUI:
         self.flight_list = ft.ListView(expand=True, spacing=5)
         
         self._setup_ui()
+        self.page.on_window_event = self._handle_window_event
         
 
         # Start background tasks
         self.page.run_task(self._update_stats_loop)
-        self.page.run_task(self._update_stats_loop)
         self.page.run_task(self._update_messages_loop)
         self.page.run_task(self._update_emails_loop)
         self.page.run_task(self._update_weather_loop)
@@ -94,9 +152,18 @@ class JarvisUI:
                 ),
                 ft.Divider(color="transparent", height=10),
                 
-                ft.Text("SYSTEM STATUS", color="cyan", weight="bold"),
+                ft.Row([
+                    ft.Text("SYSTEM STATUS", color="cyan", weight="bold"),
+                    self.pause_button,
+                ], alignment="spaceBetween"),
                 ft.Divider(color="cyan900"),
                 ft.Row([self.cpu_ring, self.mem_ring, self.batt_ring], alignment=ft.MainAxisAlignment.SPACE_EVENLY),
+                ft.Text("CORES", size=10, color="cyan700"),
+                self.core_map,
+                self.sparklines,
+                self.stat_details,
+                ft.Text("REFRESH", size=10, color="cyan700"),
+                self.interval_slider,
                 ft.Divider(color="transparent", height=20),
                 ft.Text("ACTIVE AGENTS", color="cyan", weight="bold"),
                 ft.Divider(color="cyan900"),
@@ -126,6 +193,7 @@ class JarvisUI:
                     self.input_field,
                     ft.IconButton(icon=ft.Icons.MIC, icon_color="cyan", on_click=self._toggle_mic),
                     ft.IconButton(icon=ft.Icons.SEND, icon_color="cyan", on_click=self._handle_submit),
+                    ft.IconButton(icon=ft.Icons.STOP, icon_color="cyan", tooltip="Stop", on_click=self._handle_stop),
                 ])
             ]),
             expand=True,
@@ -196,15 +264,25 @@ class JarvisUI:
             if not self.page or not self.cpu_ring.page:
                  await asyncio.sleep(1)
                  continue
+            
+            if self.stats_paused:
+                # Nothing to draw; check back less often until resumed
+                await self._wait_for_stats_tick(PAUSED_STATS_POLL_SECONDS)
+                continue
                  
             try:
-                cpu = self.system_stats.get_cpu_info()
-                mem = self.system_stats.get_memory_info()
-                batt = self.system_stats.get_battery_info()
+                stats = await self.system_stats.get_all_stats()
+                batt = stats["battery"]
                 
-                self.cpu_ring.update_value(cpu / 100.0)
-                self.mem_ring.update_value(mem['percent'] / 100.0)
-                self.batt_ring.update_value(batt['percent'] / 100.0)
+                self.cpu_ring.update_value(stats["cpu"] / 100.0)
+                self.mem_ring.update_value(stats["memory"]["percent"] / 100.0)
+                # No battery (desktop) shows as a full ring
+                self.batt_ring.update_value(batt['percent'] / 100.0 if batt else 1.0)
+                self._update_core_map(stats["per_core"])
+                self._update_stat_details(stats)
+                self._update_sparklines()
+                for alert in self._check_stat_alerts(stats):
+                    self._show_stat_alert(alert)
                 
                 # Update location occasionally or just once
                 if self.loc_text.value == "Scanning..." and self.loc_text.page:
@@ -215,7 +293,182 @@ class JarvisUI:
             except Exception as e:
                 print(f"Stats error: {e}")
                 
-            await asyncio.sleep(2)
+            # Re-read every tick; changing it wakes the loop so it applies right away
+            await self._wait_for_stats_tick(self._stats_interval_ms() / 1000)
+
+    async def _wait_for_stats_tick(self, seconds: float):
+        """Sleep until the next stats tick, or until something asks for a refresh"""
+        try:
+            await asyncio.wait_for(self._stats_wake.wait(), timeout=seconds)
+        except asyncio.TimeoutError:
+            pass
+        self._stats_wake.clear()
+
+    def pause_stats(self):
+        """Stop refreshing system stats until resume_stats"""
+        self.stats_paused = True
+
+    def resume_stats(self):
+        """Resume refreshing system stats, with a reading right away"""
+        self.stats_paused = False
+        self._stats_wake.set()
+
+    async def _toggle_stats_pause(self, e):
+        self._stats_paused_by_user = not self.stats_paused
+        if self._stats_paused_by_user:
+            self.pause_stats()
+        else:
+            self.resume_stats()
+        self.pause_button.icon = ft.Icons.PLAY_ARROW if self.stats_paused else ft.Icons.PAUSE
+        self.pause_button.tooltip = "Resume stats" if self.stats_paused else "Pause stats"
+        self.pause_button.update()
+
+    async def _handle_window_event(self, e):
+        # No point refreshing stats nobody can see
+        if e.data == "minimize":
+            self.pause_stats()
+        elif e.data == "restore" and not self._stats_paused_by_user:
+            self.resume_stats()
+
+    def _preferences(self):
+        """The memory store holding preferences, or None if memory is disabled"""
+        integration = self.orchestrator.memory_integration
+        return integration.memory if integration else None
+
+    def _stat_preference(self, category: str, key: str) -> Optional[str]:
+        """
+        A stats preference, cached so the stats loop doesn't hit SQLite
+        every tick. The cache is dropped whenever the store's preferences
+        change (the chat preference tool, set_stats_interval, ...).
+        """
+        prefs = self._preferences()
+        if not prefs:
+            return None
+        if prefs.preferences_version != self._stat_preferences_version:
+            self._stat_preferences = {}
+            self._stat_preferences_version = prefs.preferences_version
+        if (category, key) not in self._stat_preferences:
+            self._stat_preferences[(category, key)] = prefs.get_preference(category, key)
+        return self._stat_preferences[(category, key)]
+
+    def _stats_interval_ms(self) -> int:
+        """Stats refresh interval from preferences, clamped to the allowed range"""
+        return clamp_stats_interval(self._stat_preference(*STATS_INTERVAL_PREFERENCE))
+
+    def set_stats_interval(self, interval_ms: int) -> int:
+        """Change and persist the stats refresh interval; returns the clamped value"""
+        interval_ms = clamp_stats_interval(interval_ms)
+        prefs = self._preferences()
+        if prefs:
+            prefs.set_preference(*STATS_INTERVAL_PREFERENCE, str(interval_ms))
+        return interval_ms
+
+    async def _handle_interval_change(self, e):
+        self.set_stats_interval(round(float(e.control.value) * 1000))
+        self._stats_wake.set()
+
+    def _update_core_map(self, per_core: List[float]):
+        """One cell per logical core, colored by load"""
+        if len(self.core_map.controls) != len(per_core):
+            self.core_map.controls = [
+                ft.Container(width=12, height=12, border_radius=2) for _ in per_core
+            ]
+        
+        for cell, percent in zip(self.core_map.controls, per_core):
+            if percent >= 90:
+                cell.bgcolor = "red"
+            elif percent >= 60:
+                cell.bgcolor = "amber"
+            elif percent >= 25:
+                cell.bgcolor = "cyan700"
+            else:
+                cell.bgcolor = "cyan900"
+            cell.tooltip = f"{percent:.0f}%"
+        self.core_map.update()
+
+    def _update_sparklines(self):
+        """CPU and memory over the last SPARKLINE_SECONDS, from the stats history"""
+        history = self.system_stats.get_history(SPARKLINE_SECONDS)[-SPARKLINE_MAX_POINTS:]
+        cpu = _sparkline([reading["cpu"] for reading in history])
+        mem = _sparkline([reading["memory"]["percent"] for reading in history])
+        self.sparklines.controls = [
+            ft.Text(f"CPU {cpu}", size=10, color="cyan200", font_family="monospace"),
+            ft.Text(f"MEM {mem}", size=10, color="cyan200", font_family="monospace"),
+        ]
+        self.sparklines.update()
+
+    def _alert_threshold(self, metric: str) -> float:
+        """Alert threshold for a metric from preferences, or the default"""
+        stored = self._stat_preference("sensor", f"{metric}_alert_percent")
+        try:
+            return float(stored)
+        except (TypeError, ValueError):
+            return DEFAULT_ALERT_THRESHOLDS[metric]
+
+    def _check_stat_alerts(self, stats: Dict[str, Any]) -> List[Dict[str, Any]]:
+        """Alerts for CPU/memory readings that crossed their thresholds this tick"""
+        values = {"cpu": stats["cpu"], "memory": stats["memory"]["percent"]}
+        alerts = []
+        for metric, value in values.items():
+            alert = self.stat_alerts.check(metric, value, self._alert_threshold(metric))
+            if alert:
+                alerts.append(alert)
+        return alerts
+
+    def _show_stat_alert(self, alert: Dict[str, Any]):
+        name = "CPU" if alert["metric"] == "cpu" else alert["metric"].capitalize()
+        if alert["severity"] == "warning":
+            text = f"{name} at {alert['value']:.0f}% (alert threshold {alert['threshold']:.0f}%)"
+        else:
+            text = f"{name} back down to {alert['value']:.0f}%"
+        
+        self.page.snack_bar = ft.SnackBar(
+            ft.Text(text, color="white"),
+            bgcolor="red900" if alert["severity"] == "warning" else "cyan900",
+        )
+        self.page.snack_bar.open = True
+        self.page.update()
+
+    def _stat_detail_lines(self, stats: Dict[str, Any]) -> List[str]:
+        """Secondary readings listed under the rings"""
+        lines = []
+        
+        gpu = stats["gpu"]
+        if gpu["gpu_usage"] is not None:
+            line = f"GPU {gpu['gpu_usage']:.0f}%"
+            if gpu["vram_total"]:
+                line += f" · VRAM {_format_bytes(gpu['vram_used'])} / {_format_bytes(gpu['vram_total'])}"
+            lines.append(line)
+        
+        memory = stats["memory"]
+        line = f"SWAP {memory['swap_percent']:.0f}%" if memory["swap_total"] else "SWAP off"
+        if stats["load_average"] is not None:
+            line += " · LOAD " + " ".join(f"{load:.2f}" for load in stats["load_average"])
+        lines.append(line)
+        
+        for disk in stats["disks"]:
+            lines.append(
+                f"DISK {disk['mount_point']} {disk['percent']:.0f}%"
+                f" · R {_format_bytes(disk['read_bytes_per_sec'])}/s"
+                f" W {_format_bytes(disk['write_bytes_per_sec'])}/s"
+            )
+        
+        for nic in stats["networks"]:
+            if not nic["is_up"]:
+                continue
+            lines.append(
+                f"NET {nic['name']}"
+                f" · ↓ {_format_bytes(nic['received_per_sec'])}/s"
+                f" ↑ {_format_bytes(nic['transmitted_per_sec'])}/s"
+            )
+        
+        return lines
+
+    def _update_stat_details(self, stats: Dict[str, Any]):
+        self.stat_details.controls = [
+            ft.Text(line, size=10, color="cyan200") for line in self._stat_detail_lines(stats)
+        ]
+        self.stat_details.update()
 
     async def _update_messages_loop(self):
         """Periodic iMessage Sync"""
@@ -456,29 +709,44 @@ class JarvisUI:
                 width=400
             )
             
-            self.chat_list.controls.append(
-                ft.Column([response_bubble], horizontal_alignment=ft.CrossAxisAlignment.START)
-            )
+            response_column = ft.Column([response_bubble], horizontal_alignment=ft.CrossAxisAlignment.START)
+            self.chat_list.controls.append(response_column)
             self.chat_list.update()
             
             # Stream response from LLM
             full_response = []
+            self._stop_requested = False
             async for token in self.orchestrator.stream_chat(text, speak=False):
                 full_response.append(token)
                 response_bubble.content.value = "".join(full_response)
                 response_bubble.update()
             
+            stats = self.orchestrator.last_generation_stats
+            if stats and stats.completion_tokens:
+                caption = f"{stats.completion_tokens} tokens"
+                if stats.tokens_per_second:
+                    caption += f" · {stats.tokens_per_second:.1f} tok/s"
+                if stats.truncated:
+                    caption += " · cut off at the token limit"
+                response_column.controls.append(ft.Text(caption, size=10, color="grey600"))
+                self.chat_list.update()
+            
             # After streaming complete, speak the final response
             self.orb.set_state("SPEAKING")
             self.page.update()
             
-            if self.orchestrator.tts:
+            if self.orchestrator.tts and not self._stop_requested:
                 await self.orchestrator.tts.speak("".join(full_response))
             
         finally:
             self.orb.set_state("IDLE")
             self.page.update()
 
+    async def _handle_stop(self, e):
+        """Stop the response currently being generated"""
+        if self.orchestrator.cancel_generation():
+            self._stop_requested = True
+
     async def _add_message(self, text: str, is_user: bool):
         align = ft.CrossAxisAlignment.END if is_user else ft.CrossAxisAlignment.START
         bg = "#1A4D4D" if is_user else "#1A1A1A"
@@ -591,6 +859,7 @@ class JarvisUI:
 async def main_async(page: ft.Page, config_path: Optional[Path] = None):
     orchestrator = JARVISOrchestrator(config_path)
     await orchestrator.initialize()
+    orchestrator.start_warmup()
     JarvisUI(page, orchestrator)
 
 def run_ui(config_path: Optional[Path] = None):
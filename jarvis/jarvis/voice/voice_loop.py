@@ -38,6 +38,7 @@ class VoiceLoop:
     async def start(self) -> None:
         """Start the voice loop"""
         await self.orchestrator.initialize()
+        self.orchestrator.start_warmup()
         
         # Choose wake word detector
         if self.porcupine_key:
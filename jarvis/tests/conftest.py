@@ -0,0 +1,11 @@
+"""Shared fixtures for JARVIS tests"""
+
+import pytest
+
+from jarvis.core.memory_store import MemoryStore
+
+
+@pytest.fixture
+def store(tmp_path):
+    """A MemoryStore backed by a fresh database file"""
+    return MemoryStore(db_path=str(tmp_path / "memory.db"))
@@ -0,0 +1,37 @@
+"""Tests for the OpenSky traffic bounding box"""
+
+import math
+
+import pytest
+
+from jarvis.agents.connectors.flight_connector import (
+    MAX_TRAFFIC_RADIUS_MILES,
+    traffic_bbox,
+)
+
+
+def test_oversized_radius_is_clamped():
+    assert traffic_bbox(38.9, -77.0, 5000) == traffic_bbox(38.9, -77.0, MAX_TRAFFIC_RADIUS_MILES)
+
+
+@pytest.mark.parametrize("radius", [0, -10])
+def test_non_positive_radius_is_rejected(radius):
+    with pytest.raises(ValueError):
+        traffic_bbox(38.9, -77.0, radius)
+
+
+@pytest.mark.parametrize("lat", [90.0, -90.0, 89.999])
+def test_polar_latitude_gives_a_finite_box(lat):
+    lamin, lomin, lamax, lomax = traffic_bbox(lat, 10.0, 50)
+    
+    assert all(math.isfinite(v) for v in (lamin, lomin, lamax, lomax))
+    assert -90.0 <= lamin <= lamax <= 90.0
+    assert -180.0 <= lomin <= lomax <= 180.0
+
+
+def test_box_is_centered_on_the_point():
+    lamin, lomin, lamax, lomax = traffic_bbox(38.9, -77.0, 69)
+    
+    assert (lamin + lamax) / 2 == pytest.approx(38.9)
+    assert (lomin + lomax) / 2 == pytest.approx(-77.0)
+    assert lamax - lamin == pytest.approx(2.0)
@@ -0,0 +1,38 @@
+"""Tests for the memory integration's prompt context budget"""
+
+import pytest
+
+from jarvis.integrations.memory_module import DEFAULT_CONTEXT_MAX_CHARS, MemoryIntegration
+
+
+def integration(tmp_path, context_max_chars=None):
+    return MemoryIntegration(
+        db_path=str(tmp_path / "memory.db"),
+        context_max_chars=context_max_chars,
+    )
+
+
+def test_budget_scales_with_context_window(tmp_path):
+    memory = integration(tmp_path)
+    
+    assert memory.context_budget(8192) == 4096
+    assert memory.context_budget(32768) == 16384
+
+
+def test_budget_falls_back_when_context_window_unknown(tmp_path):
+    assert integration(tmp_path).context_budget(None) == DEFAULT_CONTEXT_MAX_CHARS
+
+
+@pytest.mark.parametrize("configured", [0, 500])
+def test_configured_cap_wins(tmp_path, configured):
+    assert integration(tmp_path, configured).context_budget(8192) == configured
+
+
+def test_context_is_trimmed_to_derived_budget(tmp_path):
+    memory = integration(tmp_path)
+    for i in range(5):
+        memory.memory.add_memory(f"Important detail number {i} " * 5, importance=9)
+    
+    # 256 tokens -> 128 characters
+    assert len(memory.get_context_for_prompt(256)) <= 128
+    assert len(memory.get_context_for_prompt(8192)) > 128
@@ -0,0 +1,214 @@
+"""Tests for MemoryStore memory handling"""
+
+import sqlite3
+import threading
+from datetime import datetime
+
+import pytest
+
+from jarvis.core.memory_store import MemoryStore, _match_score, effective_importance
+
+
+def importance_of(store, memory_id):
+    return next(m.importance for m in store.list_memories(limit=100).items if m.id == memory_id)
+
+
+def set_last_accessed(store, memory_id, timestamp):
+    with sqlite3.connect(store.db_path) as conn:
+        conn.execute("UPDATE memories SET last_accessed = ? WHERE id = ?", (timestamp, memory_id))
+
+
+# ========== Importance scale ==========
+
+@pytest.mark.parametrize("given, stored", [(9999, 10), (11, 10), (-5, 1), (1, 1), (10, 10)])
+def test_add_memory_clamps_importance(store, given, stored):
+    memory_id = store.add_memory("note", importance=given)
+    
+    assert importance_of(store, memory_id) == stored
+
+
+@pytest.mark.parametrize("given", [None, 0])
+def test_add_memory_applies_default_importance(tmp_path, given):
+    store = MemoryStore(db_path=str(tmp_path / "memory.db"), default_importance=3)
+    
+    memory_id = store.add_memory("note", importance=given)
+    
+    assert importance_of(store, memory_id) == 3
+
+
+def test_default_importance_is_clamped(tmp_path):
+    store = MemoryStore(db_path=str(tmp_path / "memory.db"), default_importance=50)
+    
+    assert store.default_importance == 10
+
+
+def test_update_memory_clamps_importance(store):
+    memory_id = store.add_memory("note", importance=5)
+    
+    store.update_memory(memory_id, importance=-3)
+    assert importance_of(store, memory_id) == 1
+    
+    store.update_memory(memory_id, importance=42)
+    assert importance_of(store, memory_id) == 10
+
+
+# ========== Importance floors ==========
+
+def test_floor_raises_importance_on_insert(store):
+    store.set_importance_floor("health", 8)
+    
+    memory_id = store.add_memory("Allergic to penicillin", category="health", importance=2)
+    
+    assert importance_of(store, memory_id) == 8
+
+
+def test_health_memory_is_never_pruned(store):
+    store.set_importance_floor("health", 8)
+    health_id = store.add_memory("Allergic to penicillin", category="health", importance=2)
+    store.add_memory("Liked the hotel pool", category="travel", importance=2)
+    
+    assert store.prune_memories(9) == 1
+    assert importance_of(store, health_id) == 8
+
+
+def test_floor_protects_memories_added_before_it_was_set(store):
+    health_id = store.add_memory("Takes vitamin D", category="health", importance=2)
+    
+    store.set_importance_floor("health", 8)
+    
+    assert importance_of(store, health_id) == 8
+    assert store.prune_memories(9) == 0
+
+
+# ========== Browsing ==========
+
+@pytest.fixture
+def browsable(store):
+    for i in range(5):
+        store.add_memory(f"health {i}", category="health")
+    for i in range(3):
+        store.add_memory(f"travel {i}", category="travel")
+    return store
+
+
+def test_list_memories_filters_by_category(browsable):
+    page = browsable.list_memories(category="travel")
+    
+    assert page.total == 3
+    assert [m.content for m in page.items] == ["travel 2", "travel 1", "travel 0"]
+
+
+def test_list_memories_pagination_windows(browsable):
+    first = browsable.list_memories(category="health", limit=2, offset=0)
+    second = browsable.list_memories(category="health", limit=2, offset=2)
+    last = browsable.list_memories(category="health", limit=2, offset=4)
+    
+    assert [m.content for m in first.items] == ["health 4", "health 3"]
+    assert [m.content for m in second.items] == ["health 2", "health 1"]
+    assert [m.content for m in last.items] == ["health 0"]
+    assert first.total == second.total == last.total == 5
+
+
+def test_list_memories_without_filter_returns_all_categories(browsable):
+    page = browsable.list_memories(limit=100)
+    
+    assert page.total == 8
+    assert {m.category for m in page.items} == {"health", "travel"}
+
+
+def test_list_memories_skips_expired_in_page_and_total(browsable):
+    expired = browsable.add_memory_with_ttl("old gate number", category="travel", ttl_seconds=60)
+    with sqlite3.connect(browsable.db_path) as conn:
+        conn.execute("UPDATE memories SET expires_at = '2020-01-01 00:00:00' WHERE id = ?", (expired,))
+    
+    page = browsable.list_memories(category="travel")
+    
+    assert page.total == 3
+    assert expired not in [m.id for m in page.items]
+    assert browsable.list_memories(limit=100).total == 8
+
+
+# ========== Importance decay ==========
+
+NOW = datetime(2024, 6, 1, 12, 0, 0)
+
+
+@pytest.mark.parametrize("last_accessed, expected", [
+    (datetime(2024, 6, 1, 12, 0, 0), 8.0),
+    (datetime(2024, 5, 2, 12, 0, 0), 4.0),
+    (datetime(2024, 4, 2, 12, 0, 0), 2.0),
+    ("2024-05-02 12:00:00", 4.0),
+    (None, 8.0),
+])
+def test_importance_halves_every_half_life(last_accessed, expected):
+    assert effective_importance(8, last_accessed, 30, now=NOW) == pytest.approx(expected)
+
+
+def test_non_positive_half_life_disables_decay():
+    assert effective_importance(8, datetime(2020, 1, 1), 0, now=NOW) == 8.0
+
+
+def test_future_access_does_not_inflate_importance():
+    assert effective_importance(8, datetime(2024, 7, 1), 30, now=NOW) == 8.0
+
+
+def test_importance_outweighs_a_better_keyword_match(store):
+    store.set_preference("memory", "decay_half_life_days", "0")
+    for i in range(6):
+        store.add_memory(f"Unrelated note {i}")
+    trivia = store.add_memory("budget", importance=1)
+    critical = store.add_memory("budget for the kitchen renovation project", importance=10)
+    
+    ids = [m.id for m in store.search_memories("budget").items]
+    
+    assert ids == [critical, trivia]
+
+
+def test_stale_memory_sinks_below_recently_recalled_one(store):
+    stale = store.add_memory("Quarterly budget review", importance=9)
+    recent = store.add_memory("Quarterly budget review", importance=3)
+    # 152 days apart with a 30-day half-life: 9 decays to ~0.3 relative to 3
+    set_last_accessed(store, stale, "2020-01-01 00:00:00")
+    set_last_accessed(store, recent, "2020-06-01 00:00:00")
+    
+    ids = [m.id for m in store.search_memories("budget").items]
+    
+    assert ids == [recent, stale]
+
+
+# ========== Match score ==========
+
+@pytest.mark.parametrize("text, query, score", [
+    ("Metro Center", "metro center", 1.0),
+    ("Takes the Silver Line", "silver", 0.75),
+    ("Prefers Farragut", "farra", 0.5),
+    ("Prefers Farragut", "rosslyn", 0.0),
+])
+def test_match_score_tiers(text, query, score):
+    assert _match_score(text, query) == score
+
+
+# ========== Concurrency ==========
+
+def test_concurrent_writers_and_readers_do_not_lock(store):
+    errors = []
+    
+    def worker(n):
+        try:
+            for i in range(20):
+                store.set_preference("load", f"worker{n}", str(i))
+                store.add_memory(f"Worker {n} note {i}", category="load")
+                store.get_preference("load", f"worker{n}")
+                store.list_memories(category="load", limit=5)
+        except sqlite3.OperationalError as e:
+            errors.append(e)
+    
+    threads = [threading.Thread(target=worker, args=(n,)) for n in range(8)]
+    for thread in threads:
+        thread.start()
+    for thread in threads:
+        thread.join()
+    
+    assert errors == []
+    assert store.list_memories(category="load", limit=1).total == 160
+    assert store.get_preference("load", "worker7") == "19"
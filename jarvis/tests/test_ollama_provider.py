@@ -0,0 +1,208 @@
+"""Tests for the Ollama provider's options and context window clamping"""
+
+from types import SimpleNamespace
+
+import pytest
+
+from jarvis.providers.llm.ollama_provider import (
+    MIN_COMPLETION_TOKENS,
+    OllamaProvider,
+    suggest_context_size,
+)
+
+
+class FakeClient:
+    """Answers show() with a model trained on 8192 tokens and records chat/generate calls"""
+    
+    def __init__(self, context_length=8192):
+        self.context_length = context_length
+        self.shown = []
+        self.chats = []
+        self.generates = []
+    
+    async def show(self, model):
+        self.shown.append(model)
+        return {"details": {}, "model_info": {"qwen2.context_length": self.context_length}}
+    
+    async def ps(self):
+        return {"models": []}
+    
+    async def chat(self, **kwargs):
+        self.chats.append(kwargs)
+        return SimpleNamespace(message=SimpleNamespace(content="ok", tool_calls=None))
+    
+    async def generate(self, **kwargs):
+        self.generates.append(kwargs)
+        return {}
+
+
+def provider(context_size):
+    llm = OllamaProvider(primary_model="qwen2.5:14b", context_size=context_size)
+    llm._client = FakeClient()
+    return llm
+
+
+@pytest.mark.asyncio
+async def test_num_ctx_is_clamped_to_trained_maximum():
+    llm = provider(32768)
+    
+    await llm._call_model("qwen2.5:14b", [{"role": "user", "content": "hi"}])
+    
+    assert llm._client.chats[0]["options"]["num_ctx"] == 8192
+
+
+@pytest.mark.asyncio
+async def test_smaller_context_size_is_sent_unchanged():
+    llm = provider(4096)
+    
+    await llm._call_model("qwen2.5:14b", [{"role": "user", "content": "hi"}])
+    
+    assert llm._client.chats[0]["options"]["num_ctx"] == 4096
+
+
+@pytest.mark.asyncio
+async def test_trained_maximum_is_looked_up_once_per_model():
+    llm = provider(32768)
+    
+    for _ in range(3):
+        await llm._call_model("qwen2.5:14b", [{"role": "user", "content": "hi"}])
+    
+    assert llm._client.shown == ["qwen2.5:14b"]
+
+
+@pytest.mark.asyncio
+async def test_unset_context_size_sends_no_num_ctx():
+    llm = provider(None)
+    llm.max_tokens = 16384
+    
+    await llm._call_model("qwen2.5:14b", [{"role": "user", "content": "hi"}])
+    
+    options = llm._client.chats[0]["options"]
+    assert "num_ctx" not in options
+    # The completion still can't outgrow the trained window
+    assert options["num_predict"] == 8192
+
+
+def test_completion_cap_follows_clamped_window():
+    llm = OllamaProvider(context_size=32768, max_tokens=16384)
+    
+    assert llm._options(context_length=8192)["num_predict"] == 8192
+
+
+def test_completion_cap_leaves_room_for_prompt():
+    llm = OllamaProvider(context_size=8192, max_tokens=16384)
+    
+    assert llm._options(context_length=8192, prompt_tokens=6000)["num_predict"] == 2192
+
+
+def test_completion_cap_has_floor_when_prompt_fills_window():
+    llm = OllamaProvider(context_size=4096, max_tokens=1024)
+    
+    assert llm._options(prompt_tokens=5000)["num_predict"] == MIN_COMPLETION_TOKENS
+
+
+@pytest.mark.asyncio
+async def test_prompt_size_is_estimated_from_messages():
+    llm = provider(4096)
+    llm.max_tokens = 4096
+    
+    await llm._call_model("qwen2.5:14b", [{"role": "user", "content": "x" * 8000}])
+    
+    assert llm._client.chats[0]["options"]["num_predict"] == 4096 - 2000
+
+
+@pytest.mark.asyncio
+async def test_context_window_matches_sent_num_ctx():
+    llm = provider(32768)
+    
+    window = await llm.context_window()
+    await llm._call_model("qwen2.5:14b", [{"role": "user", "content": "hi"}])
+    
+    assert window == llm._client.chats[0]["options"]["num_ctx"] == 8192
+
+
+@pytest.mark.asyncio
+async def test_warmup_loads_with_chat_options():
+    llm = OllamaProvider(primary_model="qwen2.5:14b", context_size=32768, gpu_layers=20)
+    llm._client = FakeClient()
+    
+    await llm.warmup()
+    await llm._call_model("qwen2.5:14b", [{"role": "user", "content": "hi"}])
+    
+    options = llm._client.generates[0]["options"]
+    assert options["num_ctx"] == 8192
+    assert options["num_gpu"] == 20
+    assert options["num_ctx"] == llm._client.chats[0]["options"]["num_ctx"]
+
+
+# ========== Context size suggestion ==========
+
+# 28 layers x 4 KV heads x (128 + 128) x 2 bytes
+KV_BYTES_PER_TOKEN = 57344
+
+
+def test_suggestion_never_exceeds_trained_context():
+    suggestion = suggest_context_size(KV_BYTES_PER_TOKEN, 64 * 1024 ** 3, 32768)
+    
+    assert suggestion["context_size"] == 32768
+    assert suggestion["kv_cache_bytes"] == 32768 * KV_BYTES_PER_TOKEN
+
+
+def test_suggestion_scales_down_with_smaller_budget():
+    large = suggest_context_size(KV_BYTES_PER_TOKEN, 1024 ** 3, 32768)
+    small = suggest_context_size(KV_BYTES_PER_TOKEN, 256 * 1024 ** 2, 32768)
+    
+    assert small["context_size"] < large["context_size"] <= 32768
+    assert small["kv_cache_bytes"] <= 256 * 1024 ** 2
+    assert small["context_size"] % 1024 == 0
+
+
+@pytest.mark.asyncio
+async def test_provider_suggestion_reads_attention_layout():
+    llm = provider(None)
+    
+    async def show(model):
+        return {"details": {}, "model_info": {
+            "qwen2.context_length": 32768,
+            "qwen2.block_count": 28,
+            "qwen2.embedding_length": 3584,
+            "qwen2.attention.head_count": 28,
+            "qwen2.attention.head_count_kv": 4,
+        }}
+    llm._client.show = show
+    
+    suggestion = await llm.suggest_context_size(1024 ** 3)
+    
+    assert suggestion["context_size"] == 18432
+    assert suggestion["kv_cache_bytes"] <= 1024 ** 3
+
+
+# ========== Penalties ==========
+
+def test_configured_penalties_are_sent():
+    llm = OllamaProvider(repeat_penalty=1.15, repeat_last_n=128, presence_penalty=0.5)
+    
+    options = llm._options()
+    
+    assert options["repeat_penalty"] == 1.15
+    assert options["repeat_last_n"] == 128
+    assert options["presence_penalty"] == 0.5
+    assert "frequency_penalty" not in options
+
+
+def test_unset_penalties_leave_model_defaults():
+    options = OllamaProvider()._options()
+    
+    assert not {"repeat_penalty", "frequency_penalty", "presence_penalty"} & set(options)
+
+
+@pytest.mark.parametrize("kwargs, message", [
+    ({"repeat_penalty": 5.0}, "repeat_penalty must be between 0.5 and 2, got 5"),
+    ({"repeat_penalty": 0.0}, "repeat_penalty must be between 0.5 and 2, got 0"),
+    ({"frequency_penalty": -3.0}, "frequency_penalty must be between -2 and 2, got -3"),
+    ({"presence_penalty": 2.5}, "presence_penalty must be between -2 and 2, got 2.5"),
+    ({"repeat_last_n": -2}, "repeat_last_n must be -1"),
+])
+def test_out_of_range_penalties_are_rejected(kwargs, message):
+    with pytest.raises(ValueError, match=message):
+        OllamaProvider(**kwargs)
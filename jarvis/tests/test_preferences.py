@@ -0,0 +1,212 @@
+"""Tests for MemoryStore preference handling"""
+
+import json
+import sqlite3
+
+import pytest
+
+from jarvis.core.memory_store import MemoryStore
+
+Fernet = pytest.importorskip("cryptography.fernet").Fernet
+
+
+@pytest.fixture
+def secret_store(tmp_path):
+    return MemoryStore(
+        db_path=str(tmp_path / "secret.db"),
+        secret_categories=["api_keys"],
+        cipher=Fernet(Fernet.generate_key()),
+    )
+
+
+def snapshot_data(store, label):
+    with sqlite3.connect(store.db_path) as conn:
+        row = conn.execute("SELECT data FROM preference_snapshots WHERE label = ?", (label,)).fetchone()
+    return json.loads(row[0])
+
+
+# ========== Batched writes ==========
+
+def test_batch_writes_every_pair(store):
+    store.set_preferences([
+        ("system", "city", "Arlington"),
+        ("system", "latitude", "38.88"),
+        ("system", "longitude", "-77.10"),
+    ])
+    
+    assert {p.key: p.value for p in store.get_preferences_by_category("system")} == {
+        "city": "Arlington",
+        "latitude": "38.88",
+        "longitude": "-77.10",
+    }
+
+
+def test_batch_is_all_or_nothing(store):
+    store.set_preference("system", "city", "Arlington")
+    
+    # The NOT NULL constraint fails on the second row, after the first was written
+    with pytest.raises(sqlite3.IntegrityError):
+        store.set_preferences([
+            ("system", "city", "Baltimore"),
+            ("system", "latitude", None),
+        ])
+    
+    assert store.get_preference("system", "city") == "Arlington"
+    assert store.get_preference("system", "latitude") is None
+
+
+def test_preference_writes_bump_the_version(store):
+    start = store.preferences_version
+    
+    store.set_preference("sensor", "cpu_alert_percent", "90")
+    store.set_preferences([("sensor", "memory_alert_percent", "80")])
+    store.delete_preference("sensor", "cpu_alert_percent")
+    store.get_preference("sensor", "memory_alert_percent")
+    
+    assert store.preferences_version == start + 3
+
+
+# ========== Conflicts ==========
+
+def test_same_setting_in_two_categories_is_reported(store):
+    store.set_preference("system", "latitude", "38.90")
+    store.set_preference("location", "lat", "40.71")
+    store.set_preference("system", "units", "imperial")
+    
+    conflicts = store.find_conflicting_preferences()
+    
+    assert len(conflicts) == 1
+    conflict = conflicts[0]
+    assert conflict["key"] == "latitude"
+    assert conflict["values_differ"] is True
+    assert sorted((e["category"], e["key"], e["value"]) for e in conflict["entries"]) == [
+        ("location", "lat", "40.71"),
+        ("system", "latitude", "38.90"),
+    ]
+
+
+def test_matching_values_are_flagged_as_agreeing(store):
+    store.set_preference("system", "timezone", "America/New_York")
+    store.set_preference("location", "tz", "America/New_York")
+    
+    [conflict] = store.find_conflicting_preferences()
+    
+    assert conflict["key"] == "timezone"
+    assert conflict["values_differ"] is False
+
+
+def test_single_category_is_not_a_conflict(store):
+    store.set_preference("location", "lat", "38.90")
+    store.set_preference("location", "latitude", "38.90")
+    
+    assert store.find_conflicting_preferences() == []
+
+
+# ========== Snapshots ==========
+
+def test_snapshot_diff_reports_only_the_changed_key(store):
+    store.set_preference("system", "units", "imperial")
+    store.set_preference("api_keys", "wmata", "old-key")
+    store.snapshot_preferences("before")
+    
+    store.set_preference("api_keys", "wmata", "new-key")
+    store.snapshot_preferences("after")
+    
+    assert store.diff_preferences("before", "after") == {
+        "added": {},
+        "removed": {},
+        "changed": {"api_keys/wmata": ["old-key", "new-key"]},
+    }
+
+
+def test_snapshot_diff_reports_added_and_removed_keys(store):
+    store.set_preference("system", "units", "imperial")
+    store.snapshot_preferences("before")
+    
+    store.delete_preference("system", "units")
+    store.set_preference("system", "city", "Arlington")
+    store.snapshot_preferences("after")
+    
+    diff = store.diff_preferences("before", "after")
+    
+    assert diff["added"] == {"system/city": "Arlington"}
+    assert diff["removed"] == {"system/units": "imperial"}
+    assert diff["changed"] == {}
+
+
+def test_diff_with_unknown_snapshot_raises(store):
+    store.snapshot_preferences("before")
+    
+    with pytest.raises(ValueError, match="missing"):
+        store.diff_preferences("before", "missing")
+
+
+def test_snapshot_keeps_secrets_encrypted(secret_store):
+    secret_store.set_preference("api_keys", "wmata", "hunter2")
+    
+    secret_store.snapshot_preferences("before")
+    
+    stored = snapshot_data(secret_store, "before")["api_keys/wmata"]
+    assert stored.startswith("enc:v1:")
+    assert "hunter2" not in stored
+
+
+def test_snapshot_diff_decrypts_secrets(secret_store):
+    secret_store.set_preference("api_keys", "wmata", "old-key")
+    secret_store.set_preference("api_keys", "opensky", "same-key")
+    secret_store.snapshot_preferences("before")
+    
+    secret_store.set_preference("api_keys", "wmata", "new-key")
+    # Re-encrypting an unchanged value gives a new token but isn't a change
+    secret_store.set_preference("api_keys", "opensky", "same-key")
+    secret_store.snapshot_preferences("after")
+    
+    assert secret_store.diff_preferences("before", "after")["changed"] == {
+        "api_keys/wmata": ["old-key", "new-key"],
+    }
+
+
+def test_global_search_masks_secret_values(secret_store):
+    secret_store.set_preference("api_keys", "wmata", "hunter2")
+    secret_store.set_preference("system", "wmata_line", "silver")
+    
+    hits = {r.ref: r.text for r in secret_store.global_search("wmata")}
+    
+    assert hits["api_keys/wmata"] == "api_keys/wmata: ********"
+    assert hits["system/wmata_line"] == "system/wmata_line: silver"
+
+
+# ========== Export / import ==========
+
+def test_export_writes_secrets_decrypted_and_marked_sensitive(secret_store, tmp_path):
+    secret_store.set_preference("api_keys", "wmata", "hunter2")
+    secret_store.set_preference("system", "units", "imperial")
+    path = tmp_path / "export.json"
+    
+    counts = secret_store.export_memory(str(path))
+    
+    preferences = json.loads(path.read_text())["preferences"]
+    assert counts["sensitive"] == 1
+    assert preferences == [
+        {"category": "api_keys", "key": "wmata", "value": "hunter2", "sensitive": True},
+        {"category": "system", "key": "units", "value": "imperial", "sensitive": False},
+    ]
+
+
+def test_import_reencrypts_secrets_with_the_new_key(secret_store, tmp_path):
+    secret_store.set_preference("api_keys", "wmata", "hunter2")
+    path = tmp_path / "export.json"
+    secret_store.export_memory(str(path))
+    
+    # A different machine: same secret categories, different keychain key
+    other = MemoryStore(
+        db_path=str(tmp_path / "other.db"),
+        secret_categories=["api_keys"],
+        cipher=Fernet(Fernet.generate_key()),
+    )
+    other.import_memory(str(path))
+    
+    with sqlite3.connect(other.db_path) as conn:
+        stored = conn.execute("SELECT value FROM preferences WHERE key = 'wmata'").fetchone()[0]
+    assert stored.startswith("enc:v1:")
+    assert other.get_preference("api_keys", "wmata") == "hunter2"
@@ -0,0 +1,78 @@
+"""Tests for the integration self-test runner"""
+
+import asyncio
+
+import pytest
+
+from jarvis.core.selftest import check_service, run_selftest
+
+
+async def reachable():
+    return None
+
+
+async def bad_key():
+    return "API key rejected (HTTP 401)"
+
+
+async def blocked():
+    raise ConnectionError("connection refused")
+
+
+async def hung():
+    await asyncio.sleep(10)
+
+
+@pytest.mark.asyncio
+async def test_reachable_service_reports_ok():
+    result = await check_service(reachable, timeout=1)
+    
+    assert result["ok"] is True
+    assert result["error"] is None
+    assert result["latency_ms"] >= 0
+
+
+@pytest.mark.asyncio
+async def test_failed_check_reports_its_reason():
+    result = await check_service(bad_key, timeout=1)
+    
+    assert result["ok"] is False
+    assert result["error"] == "API key rejected (HTTP 401)"
+
+
+@pytest.mark.asyncio
+async def test_exception_message_is_reported():
+    result = await check_service(blocked, timeout=1)
+    
+    assert result["ok"] is False
+    assert result["error"] == "connection refused"
+
+
+@pytest.mark.asyncio
+async def test_hung_service_times_out():
+    result = await check_service(hung, timeout=0.05)
+    
+    assert result["ok"] is False
+    assert result["error"] == "timed out after 0.05s"
+
+
+@pytest.mark.asyncio
+async def test_services_run_concurrently():
+    async def slow():
+        await asyncio.sleep(0.2)
+    
+    results = await run_selftest({"weather": slow, "wmata": slow, "location": slow}, timeout=1)
+    
+    assert set(results) == {"weather", "wmata", "location"}
+    assert all(r["ok"] for r in results.values())
+    # Run one after another these would take at least 600 ms
+    assert max(r["latency_ms"] for r in results.values()) < 500
+
+
+@pytest.mark.asyncio
+async def test_one_failure_does_not_hide_the_others():
+    results = await run_selftest({"weather": reachable, "wmata": bad_key, "opensky": blocked}, timeout=1)
+    
+    assert results["weather"]["ok"] is True
+    assert results["wmata"]["error"] == "API key rejected (HTTP 401)"
+    assert results["opensky"]["error"] == "connection refused"
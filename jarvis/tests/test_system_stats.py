@@ -0,0 +1,85 @@
+"""Tests for system stats helpers"""
+
+import pytest
+
+from jarvis.core.system_stats import (
+    DEFAULT_STATS_INTERVAL_MS,
+    MAX_STATS_INTERVAL_MS,
+    MIN_STATS_INTERVAL_MS,
+    SystemStats,
+    ThresholdMonitor,
+    clamp_stats_interval,
+)
+
+
+# ========== Refresh interval ==========
+
+@pytest.mark.parametrize("stored, expected", [
+    ("1500", 1500),
+    (10, MIN_STATS_INTERVAL_MS),
+    (10 ** 9, MAX_STATS_INTERVAL_MS),
+    (None, DEFAULT_STATS_INTERVAL_MS),
+    ("fast", DEFAULT_STATS_INTERVAL_MS),
+])
+def test_stats_interval_is_parsed_and_clamped(stored, expected):
+    assert clamp_stats_interval(stored) == expected
+
+
+# ========== History ==========
+
+def reading(timestamp, cpu=10.0):
+    return {"timestamp": timestamp, "cpu": cpu}
+
+
+def test_history_returns_requested_window_oldest_first():
+    stats = SystemStats()
+    for t in (100.0, 130.0, 150.0, 160.0):
+        stats.record(reading(t))
+    
+    window = stats.get_history(30, now=160.0)
+    
+    assert [r["timestamp"] for r in window] == [130.0, 150.0, 160.0]
+
+
+def test_history_evicts_beyond_capacity():
+    stats = SystemStats(history_size=3)
+    for t in range(5):
+        stats.record(reading(float(t)))
+    
+    assert [r["timestamp"] for r in stats.get_history(100, now=4.0)] == [2.0, 3.0, 4.0]
+
+
+# ========== Threshold alerts ==========
+
+def test_alert_fires_once_when_crossing_threshold():
+    monitor = ThresholdMonitor(hysteresis=5)
+    
+    assert monitor.check("cpu", 50, 90) is None
+    alert = monitor.check("cpu", 93, 90)
+    
+    assert alert == {"metric": "cpu", "value": 93, "threshold": 90, "severity": "warning"}
+    assert monitor.check("cpu", 97, 90) is None
+
+
+def test_hovering_at_threshold_does_not_flap():
+    monitor = ThresholdMonitor(hysteresis=5)
+    monitor.check("cpu", 91, 90)
+    
+    # Dipping just under the threshold is inside the hysteresis band
+    assert [monitor.check("cpu", v, 90) for v in (89, 91, 86, 90)] == [None] * 4
+
+
+def test_recovery_below_hysteresis_band_reports_ok():
+    monitor = ThresholdMonitor(hysteresis=5)
+    monitor.check("memory", 88, 85)
+    
+    assert monitor.check("memory", 79, 85)["severity"] == "ok"
+    assert monitor.check("memory", 86, 85)["severity"] == "warning"
+
+
+def test_metrics_are_tracked_independently():
+    monitor = ThresholdMonitor(hysteresis=5)
+    monitor.check("cpu", 95, 90)
+    
+    assert monitor.check("memory", 90, 85)["severity"] == "warning"
+    assert monitor.check("cpu", 95, 90) is None
@@ -0,0 +1,25 @@
+"""Tests for version info"""
+
+import platform
+import re
+from pathlib import Path
+
+import jarvis
+from jarvis.core.version import OPTIONAL_EXTRAS, get_version_info
+
+
+def test_version_matches_package_and_pyproject():
+    pyproject = (Path(__file__).parent.parent / "pyproject.toml").read_text()
+    declared = re.search(r'^version = "([^"]+)"', pyproject, re.M).group(1)
+    
+    info = get_version_info()
+    
+    assert info["version"] == jarvis.__version__ == declared
+
+
+def test_reports_python_version_and_every_extra():
+    info = get_version_info()
+    
+    assert info["python"] == platform.python_version()
+    assert set(info["extras"]) == set(OPTIONAL_EXTRAS)
+    assert all(isinstance(v, bool) for v in info["extras"].values())
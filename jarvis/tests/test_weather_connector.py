@@ -0,0 +1,103 @@
+"""Tests for the weather.gov connector's points metadata"""
+
+import pytest
+
+from jarvis.agents.connectors.connector_base import ConnectorConfig
+from jarvis.agents.connectors.weather_connector import WeatherConnector
+
+
+# Trimmed /points/38.8951,-77.0364 response from api.weather.gov
+POINTS_RESPONSE = {
+    "properties": {
+        "cwa": "LWX",
+        "gridId": "LWX",
+        "gridX": 97,
+        "gridY": 71,
+        "forecast": "https://api.weather.gov/gridpoints/LWX/97,71/forecast",
+        "forecastHourly": "https://api.weather.gov/gridpoints/LWX/97,71/forecast/hourly",
+        "observationStations": "https://api.weather.gov/gridpoints/LWX/97,71/stations",
+        "relativeLocation": {
+            "properties": {"city": "Washington", "state": "DC"},
+        },
+        "timeZone": "America/New_York",
+        "radarStation": "KLWX",
+    }
+}
+
+
+class FakeResponse:
+    def __init__(self, status_code, data):
+        self.status_code = status_code
+        self._data = data
+    
+    def json(self):
+        return self._data
+
+
+class FakeClient:
+    def __init__(self, data, status_code=200):
+        self.data = data
+        self.status_code = status_code
+        self.urls = []
+    
+    async def get(self, url):
+        self.urls.append(url)
+        return FakeResponse(self.status_code, self.data)
+
+
+@pytest.fixture
+def connector():
+    connector = WeatherConnector(ConnectorConfig(name="weather.gov", connector_type="weather"))
+    connector._client = FakeClient(POINTS_RESPONSE)
+    return connector
+
+
+@pytest.mark.asyncio
+async def test_metadata_parsed_from_points_response(connector):
+    metadata = await connector.get_weather_metadata({"lat": 38.8951, "lon": -77.0364})
+    
+    assert metadata == {
+        "office": "LWX",
+        "grid_id": "LWX",
+        "grid_x": 97,
+        "grid_y": 71,
+        "radar_station": "KLWX",
+        "time_zone": "America/New_York",
+    }
+    assert connector._client.urls == ["https://api.weather.gov/points/38.8951,-77.0364"]
+
+
+@pytest.mark.asyncio
+async def test_points_lookup_is_cached(connector):
+    coords = {"lat": 38.8951, "lon": -77.0364}
+    await connector.get_weather_metadata(coords)
+    await connector.get_weather_metadata(coords)
+    
+    assert len(connector._client.urls) == 1
+
+
+# ========== Self-test probe ==========
+
+@pytest.mark.asyncio
+async def test_probe_reuses_the_existing_client(connector):
+    client = connector._client
+    
+    assert await connector.probe() is None
+    assert await connector.probe() is None
+    
+    assert connector._client is client
+    assert client.urls == ["https://api.weather.gov"] * 2
+
+
+@pytest.mark.asyncio
+async def test_probe_reports_http_status(connector):
+    connector._client.status_code = 503
+    
+    assert await connector.probe() == "HTTP 503"
+
+
+@pytest.mark.asyncio
+async def test_probe_without_client_reports_not_connected(connector):
+    connector._client = None
+    
+    assert await connector.probe() == "not connected"
@@ -0,0 +1,105 @@
+"""Tests for WMATA prediction parsing and station lookup"""
+
+import pytest
+
+from jarvis.agents.connectors.connector_base import ConnectorConfig
+from jarvis.agents.connectors.wmata_connector import (
+    PredictionKind,
+    WMATAConnector,
+    find_stations_by_name,
+    parse_prediction_minutes,
+)
+
+
+# ========== Prediction minutes ==========
+
+@pytest.mark.parametrize("raw, expected", [
+    ("BRD", (PredictionKind.BOARDING, 0)),
+    ("ARR", (PredictionKind.ARRIVING, 0)),
+    ("---", (PredictionKind.UNKNOWN, None)),
+    ("", (PredictionKind.UNKNOWN, None)),
+    (None, (PredictionKind.UNKNOWN, None)),
+    (" brd ", (PredictionKind.BOARDING, 0)),
+])
+def test_special_minute_values(raw, expected):
+    assert parse_prediction_minutes(raw) == expected
+
+
+@pytest.mark.parametrize("raw, minutes", [("7", 7), ("0", 0), (12, 12)])
+def test_numeric_minutes(raw, minutes):
+    assert parse_prediction_minutes(raw) == (PredictionKind.SCHEDULED, minutes)
+
+
+def test_unknown_sorts_separately_from_boarding():
+    # Unknown must not look like a train arriving now
+    assert parse_prediction_minutes("---")[1] is None
+    assert parse_prediction_minutes("BRD")[1] == 0
+
+
+# ========== Station lookup ==========
+
+@pytest.fixture
+def connector():
+    return WMATAConnector(ConnectorConfig(name="wmata", connector_type="wmata"))
+
+
+def test_shared_word_ties_and_breaks_by_name():
+    matches = find_stations_by_name("farragut")
+    
+    assert [(name, code) for name, code, _ in matches] == [
+        ("farragut north", "A02"),
+        ("farragut west", "C03"),
+    ]
+    # Equal scores mark the query as ambiguous
+    assert matches[0][2] == matches[1][2]
+
+
+def test_ambiguous_name_resolves_to_first_by_name(connector):
+    assert connector._resolve_station("Farragut") == "A02"
+
+
+def test_full_name_beats_shared_word():
+    assert find_stations_by_name("farragut west")[0][:2] == ("farragut west", "C03")
+
+
+@pytest.mark.parametrize("query", ["a", "ro", "n"])
+def test_short_queries_match_nothing(query):
+    assert find_stations_by_name(query) == []
+
+
+@pytest.mark.parametrize("query, code", [
+    ("rosslyn station", "C05"),
+    ("ross", "C05"),
+    ("metro centre", "A01"),
+])
+def test_partial_and_misspelled_names_resolve(connector, query, code):
+    assert connector._resolve_station(query) == code
+
+
+# ========== Base URL ==========
+
+@pytest.mark.asyncio
+async def test_overridden_base_url_receives_train_requests():
+    httpx = pytest.importorskip("httpx")
+    requests = []
+    
+    def handler(request):
+        requests.append(request)
+        return httpx.Response(200, json={"Trains": [
+            {"Line": "OR", "DestinationName": "Vienna", "Min": "4", "Car": "8"},
+        ]})
+    
+    connector = WMATAConnector(ConnectorConfig(
+        name="wmata",
+        connector_type="wmata",
+        api_key="test-key",
+        extra={"base_url": "http://wmata.mock:8080/"},
+    ))
+    connector._client = httpx.AsyncClient(transport=httpx.MockTransport(handler))
+    
+    trains = await connector.search({"station": "Rosslyn", "mode": "metro"})
+    
+    assert [request.url.host for request in requests] == ["wmata.mock"]
+    assert requests[0].url.port == 8080
+    assert requests[0].url.path == "/StationPrediction.svc/json/GetPrediction/C05"
+    assert trains[0]["destination"] == "Vienna"